@@ -0,0 +1,124 @@
+use std::fmt;
+
+use jsonrpsee::core::ClientError;
+
+/// Classified error for calls made through the generated RPC clients.
+///
+/// Wraps jsonrpsee's `ClientError` into categories consumers can act on.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ConsensusClientError {
+    /// The connection or the underlying transport failed
+    Transport(String),
+    /// The request did not complete in time
+    Timeout,
+    /// The server answered with a JSON-RPC error object
+    ServerRejected { code: i32, message: String },
+    /// The response could not be decoded
+    Decode(String),
+    /// The client refused the request locally, e.g. an empty batch or an operation the
+    /// transport doesn't support; retrying won't help
+    Local(String),
+}
+
+impl From<ClientError> for ConsensusClientError {
+    fn from(err: ClientError) -> Self {
+        match err {
+            ClientError::Call(obj) => Self::ServerRejected {
+                code: obj.code(),
+                message: obj.message().to_string(),
+            },
+            ClientError::RequestTimeout => Self::Timeout,
+            ClientError::ParseError(err) => Self::Decode(err.to_string()),
+            ClientError::InvalidSubscriptionId | ClientError::InvalidRequestId(_) => {
+                Self::Decode(err.to_string())
+            }
+            ClientError::Transport(_)
+            | ClientError::RestartNeeded(_)
+            | ClientError::ServiceDisconnect => Self::Transport(err.to_string()),
+            ClientError::Custom(_)
+            | ClientError::HttpNotImplemented
+            | ClientError::EmptyBatchRequest(_)
+            | ClientError::RegisterMethod(_) => Self::Local(err.to_string()),
+        }
+    }
+}
+
+impl fmt::Display for ConsensusClientError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Transport(msg) => write!(f, "transport error: {msg}"),
+            Self::Timeout => write!(f, "request timed out"),
+            Self::ServerRejected { code, message } => {
+                write!(f, "server rejected request ({code}): {message}")
+            }
+            Self::Decode(msg) => write!(f, "failed to decode response: {msg}"),
+            Self::Local(msg) => write!(f, "request rejected by the client: {msg}"),
+        }
+    }
+}
+
+impl std::error::Error for ConsensusClientError {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use jsonrpsee::types::ErrorObjectOwned;
+
+    #[test]
+    fn test_call_error_maps_to_server_rejected() {
+        let err = ClientError::Call(ErrorObjectOwned::owned(-32000, "rejected", None::<()>));
+        assert_eq!(
+            ConsensusClientError::from(err),
+            ConsensusClientError::ServerRejected {
+                code: -32000,
+                message: "rejected".to_string(),
+            }
+        );
+    }
+
+    #[test]
+    fn test_timeout_maps_to_timeout() {
+        assert_eq!(
+            ConsensusClientError::from(ClientError::RequestTimeout),
+            ConsensusClientError::Timeout
+        );
+    }
+
+    #[test]
+    fn test_transport_error_maps_to_transport() {
+        let io = std::io::Error::new(std::io::ErrorKind::ConnectionRefused, "refused");
+        let err = ClientError::Transport(io.into());
+        assert!(matches!(
+            ConsensusClientError::from(err),
+            ConsensusClientError::Transport(_)
+        ));
+    }
+
+    #[test]
+    fn test_parse_error_maps_to_decode() {
+        let json_err = serde_json::from_str::<u64>("not json").unwrap_err();
+        let err = ClientError::ParseError(json_err);
+        assert!(matches!(
+            ConsensusClientError::from(err),
+            ConsensusClientError::Decode(_)
+        ));
+    }
+
+    #[test]
+    fn test_client_misuse_maps_to_local() {
+        for err in [
+            ClientError::Custom("unsupported".to_string()),
+            ClientError::HttpNotImplemented,
+            ClientError::EmptyBatchRequest(
+                jsonrpsee::core::params::BatchRequestBuilder::new()
+                    .build()
+                    .unwrap_err(),
+            ),
+        ] {
+            assert!(matches!(
+                ConsensusClientError::from(err),
+                ConsensusClientError::Local(_)
+            ));
+        }
+    }
+}
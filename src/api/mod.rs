@@ -1,5 +1,9 @@
+#[cfg(feature = "client-extras")]
+mod client_error;
 mod mysticeti;
 mod rawtx;
 
+#[cfg(feature = "client-extras")]
+pub use client_error::*;
 pub use mysticeti::*;
 pub use rawtx::*;
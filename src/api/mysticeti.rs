@@ -1,6 +1,12 @@
-use jsonrpsee::{core::RpcResult, proc_macros::rpc};
+use jsonrpsee::{
+    core::{RpcResult, SubscriptionResult},
+    proc_macros::rpc,
+};
 
-use crate::CommittedSubDag;
+use crate::{Bytes, CommitRef, CommittedSubDag};
+
+/// Maximum number of transactions per notification of `streamCommitTransactions`.
+pub const COMMIT_TRANSACTIONS_CHUNK_SIZE: usize = 1024;
 
 /// trait interface for a custom rpc namespace: `txpool`
 ///
@@ -19,12 +25,57 @@ pub trait MysticetiConsensusApi {
         &self,
         #[argument(rename = "subdag")] subdag: CommittedSubDag,
     ) -> RpcResult<()>;
+
+    /// Lowest commit round still retained by the server.
+    ///
+    /// Commits below this round have been pruned and must be obtained from a snapshot instead.
+    /// `0` means nothing has been pruned.
+    #[method(name = "pruningHorizon")]
+    async fn pruning_horizon(&self) -> RpcResult<u64>;
+
+    /// Stream the transactions of the commit identified by `commit_ref`, in flatten order.
+    ///
+    /// Each notification carries at most [`COMMIT_TRANSACTIONS_CHUNK_SIZE`] transactions and the
+    /// server closes the subscription after the last chunk has been sent.
+    #[subscription(
+        name = "streamCommitTransactions",
+        unsubscribe = "unsubscribeCommitTransactions",
+        item = Vec<Bytes>
+    )]
+    fn stream_commit_transactions(&self, commit_ref: CommitRef) -> SubscriptionResult;
 }
 
 #[cfg(test)]
 mod tests {
-    use crate::types::{BlockRef, CommitRef, Transaction};
-    use crate::{BlockDigest, CommittedSubDag, SignedBlock, VerifiedBlock};
+    use super::*;
+    use crate::types::{BlockRef, Transaction};
+    use crate::{BlockDigest, SignedBlock, VerifiedBlock};
+    use jsonrpsee::PendingSubscriptionSink;
+
+    struct MockMysticetiConsensusApi;
+
+    #[jsonrpsee::core::async_trait]
+    impl MysticetiConsensusApiServer for MockMysticetiConsensusApi {
+        fn submit_committed_subdags(&self, _subdags: Vec<CommittedSubDag>) -> RpcResult<()> {
+            Ok(())
+        }
+
+        fn submit_committed_subdag(&self, _subdag: CommittedSubDag) -> RpcResult<()> {
+            Ok(())
+        }
+
+        async fn pruning_horizon(&self) -> RpcResult<u64> {
+            Ok(0)
+        }
+
+        fn stream_commit_transactions(
+            &self,
+            _subscription_sink: PendingSubscriptionSink,
+            _commit_ref: CommitRef,
+        ) -> SubscriptionResult {
+            Ok(())
+        }
+    }
 
     fn create_test_block_ref(round: u64) -> BlockRef {
         let mut digest = [0u8; 32];
@@ -33,7 +84,6 @@ mod tests {
             digest,
             round,
             leader_address: String::new(),
-            ..Default::default()
         }
     }
 
@@ -74,7 +124,7 @@ mod tests {
         // Test that Vec<CommittedSubDag> works as expected
         let subdag1 = create_test_committed_subdag();
         let subdag2 = create_test_committed_subdag();
-        let subdags = vec![subdag1, subdag2];
+        let subdags = Vec::from([subdag1, subdag2]);
         assert_eq!(subdags.len(), 2);
     }
 
@@ -86,4 +136,18 @@ mod tests {
         let deserialized: CommittedSubDag = serde_json::from_str(&serialized).unwrap();
         assert_eq!(subdag.timestamp_ms, deserialized.timestamp_ms);
     }
+
+    #[test]
+    fn test_stream_commit_transactions_registered() {
+        let module = MockMysticetiConsensusApi.into_rpc();
+        let names: Vec<_> = module.method_names().collect();
+        assert!(names.contains(&"mysticeti_streamCommitTransactions"));
+    }
+
+    #[test]
+    fn test_pruning_horizon_registered() {
+        let module = MockMysticetiConsensusApi.into_rpc();
+        let names: Vec<_> = module.method_names().collect();
+        assert!(names.contains(&"mysticeti_pruningHorizon"));
+    }
 }
@@ -2,9 +2,15 @@ use jsonrpsee::{
     core::{RpcResult, SubscriptionResult},
     proc_macros::rpc,
 };
+
+use crate::CommitRef;
+
 /// Bytes type alias for raw transaction data.
 /// Using Vec<u8> for better serialization support without external dependencies.
 pub type Bytes = Vec<u8>;
+/// Upper bound for the `window_ms` argument of `subscribeRawTransactionsWindowed`.
+/// Servers should reject larger windows instead of buffering unbounded amounts of data.
+pub const MAX_RAW_TRANSACTIONS_WINDOW_MS: u64 = 60_000;
 /// trait interface for a custom rpc namespace: `txpool`
 ///
 /// This defines an additional namespace where all methods are configured as trait functions.
@@ -16,14 +22,104 @@ pub trait RawTransactionApi {
     /// Send multiple raw transactions to the network in a batch.
     #[method(name = "sendRawTransactionsAsync")]
     async fn send_raw_transactions_async(&self, transactions: Vec<Bytes>) -> RpcResult<()>;
+    /// Send a raw transaction and wait until it has been committed.
+    ///
+    /// Returns the reference of the commit containing the transaction. If the transaction is not
+    /// committed within `timeout_ms` milliseconds, an error is returned; the transaction may
+    /// still be committed later.
+    #[method(name = "sendRawTransactionAndConfirm")]
+    async fn send_raw_transaction_and_confirm(
+        &self,
+        bytes: Bytes,
+        timeout_ms: u64,
+    ) -> RpcResult<CommitRef>;
     /// Creates a subscription that listens to all raw transactions when it comes to rpc server.
     #[subscription(name = "subscribeRawTransactions", item = Vec<Bytes>)]
     fn subscribe_raw_transactions(&self) -> SubscriptionResult;
+    /// Creates a subscription that buffers incoming raw transactions and flushes them as one
+    /// notification every `window_ms` milliseconds.
+    ///
+    /// Windows larger than [`MAX_RAW_TRANSACTIONS_WINDOW_MS`] are rejected. A window in which
+    /// no transaction arrived sends nothing.
+    #[subscription(name = "subscribeRawTransactionsWindowed", item = Vec<Bytes>)]
+    fn subscribe_raw_transactions_windowed(&self, window_ms: u64) -> SubscriptionResult;
+}
+
+/// Split `txs` into `shards` buckets, putting each transaction into bucket
+/// `key(tx) % shards`. Transactions keep their relative order within a bucket.
+///
+/// With `shards == 0` there is nowhere to route to, so an empty `Vec` is returned and the
+/// transactions are dropped.
+pub fn route_transactions<F: Fn(&[u8]) -> u32>(
+    txs: Vec<Bytes>,
+    shards: u32,
+    key: F,
+) -> Vec<Vec<Bytes>> {
+    if shards == 0 {
+        return Vec::new();
+    }
+    let mut buckets = vec![Vec::new(); shards as usize];
+    for tx in txs {
+        let shard = key(&tx) % shards;
+        buckets[shard as usize].push(tx);
+    }
+    buckets
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use jsonrpsee::PendingSubscriptionSink;
+
+    struct MockRawTransactionApi;
+
+    #[jsonrpsee::core::async_trait]
+    impl RawTransactionApiServer for MockRawTransactionApi {
+        async fn send_raw_transaction_async(&self, _bytes: Bytes) -> RpcResult<()> {
+            Ok(())
+        }
+
+        async fn send_raw_transactions_async(&self, _transactions: Vec<Bytes>) -> RpcResult<()> {
+            Ok(())
+        }
+
+        async fn send_raw_transaction_and_confirm(
+            &self,
+            _bytes: Bytes,
+            _timeout_ms: u64,
+        ) -> RpcResult<CommitRef> {
+            Ok(CommitRef::default())
+        }
+
+        fn subscribe_raw_transactions(
+            &self,
+            _subscription_sink: PendingSubscriptionSink,
+        ) -> SubscriptionResult {
+            Ok(())
+        }
+
+        fn subscribe_raw_transactions_windowed(
+            &self,
+            _subscription_sink: PendingSubscriptionSink,
+            _window_ms: u64,
+        ) -> SubscriptionResult {
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn test_windowed_subscription_registered() {
+        let module = MockRawTransactionApi.into_rpc();
+        let names: Vec<_> = module.method_names().collect();
+        assert!(names.contains(&"rawtx_subscribeRawTransactionsWindowed"));
+    }
+
+    #[test]
+    fn test_send_and_confirm_registered() {
+        let module = MockRawTransactionApi.into_rpc();
+        let names: Vec<_> = module.method_names().collect();
+        assert!(names.contains(&"rawtx_sendRawTransactionAndConfirm"));
+    }
 
     #[test]
     fn test_bytes_type_alias() {
@@ -66,4 +162,19 @@ mod tests {
         let bytes: Bytes = vec![0u8; 1000];
         assert_eq!(bytes.len(), 1000);
     }
+
+    #[test]
+    fn test_route_transactions() {
+        let txs = vec![vec![1, 0], vec![2], vec![3, 9], vec![4], vec![]];
+        let shards = route_transactions(txs, 2, |tx| tx.first().copied().unwrap_or(0) as u32);
+        assert_eq!(
+            shards,
+            vec![vec![vec![2], vec![4], vec![]], vec![vec![1, 0], vec![3, 9]]]
+        );
+    }
+
+    #[test]
+    fn test_route_transactions_zero_shards() {
+        assert!(route_transactions(vec![vec![1]], 0, |_| 0).is_empty());
+    }
 }
@@ -0,0 +1,402 @@
+//! Helpers operating over a batch of committed subdags.
+
+use std::collections::HashSet;
+
+use crate::types::{CommitRef, CounterOverflow, Transaction};
+use crate::CommittedSubDag;
+
+/// Iterate over every transaction of a batch, tagged with the commit it belongs to.
+pub fn iter_batch_transactions(
+    subdags: &[CommittedSubDag],
+) -> impl Iterator<Item = (CommitRef, &Transaction)> {
+    subdags
+        .iter()
+        .flat_map(|subdag| subdag.transactions().map(move |tx| (subdag.commit_ref, tx)))
+}
+
+/// Sort a batch by commit round, ascending. Subdags with the same round keep their input order.
+pub fn sort_subdag_batch(mut subdags: Vec<CommittedSubDag>) -> Vec<CommittedSubDag> {
+    subdags.sort_by_key(|subdag| subdag.commit_ref.round);
+    subdags
+}
+
+/// Interleave the transactions of two subdags.
+///
+/// Transactions don't carry a round, so the interleaving is done block by block: all
+/// transactions of `a`'s first block, then those of `b`'s first block, then `a`'s second block,
+/// and so on. Once one subdag runs out of blocks, the remaining blocks of the other follow.
+pub fn interleave_by_round<'a>(
+    a: &'a CommittedSubDag,
+    b: &'a CommittedSubDag,
+) -> Vec<&'a Transaction> {
+    let mut transactions = Vec::with_capacity(a.len() + b.len());
+    for i in 0..a.blocks.len().max(b.blocks.len()) {
+        for subdag in [a, b] {
+            if let Some(block) = subdag.blocks.get(i) {
+                transactions.extend(block.block.transactions());
+            }
+        }
+    }
+    transactions
+}
+
+/// Commit round and transaction count of each subdag, in input order.
+pub fn throughput_by_round(subdags: &[CommittedSubDag]) -> Vec<(u64, usize)> {
+    subdags
+        .iter()
+        .map(|subdag| (subdag.commit_ref.round as u64, subdag.len()))
+        .collect()
+}
+
+/// Serialize a batch of subdags as a single JSON array.
+pub fn serialize_subdag_batch(subdags: &[CommittedSubDag]) -> Result<String, serde_json::Error> {
+    serde_json::to_string(subdags)
+}
+
+/// Deserialize a JSON array produced by [`serialize_subdag_batch`].
+pub fn deserialize_subdag_batch(json: &str) -> Result<Vec<CommittedSubDag>, serde_json::Error> {
+    serde_json::from_str(json)
+}
+
+/// Running transaction count over a sequence of subdags.
+///
+/// The count is kept as a `u64` with checked addition, so it neither depends on the width of
+/// `usize` nor silently wraps around.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct CumulativeCounter {
+    total: u64,
+}
+
+impl CumulativeCounter {
+    /// Create a counter starting at zero
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Create a counter resuming from a previously persisted total
+    pub fn from_total(total: u64) -> Self {
+        Self { total }
+    }
+
+    /// Get the current total
+    pub fn total(&self) -> u64 {
+        self.total
+    }
+
+    /// Add the transactions of `subdag` and return the new total.
+    ///
+    /// On overflow the counter is left unchanged.
+    pub fn add(&mut self, subdag: &CommittedSubDag) -> Result<u64, CounterOverflow> {
+        let increment = subdag.len() as u64;
+        self.total = self.total.checked_add(increment).ok_or(CounterOverflow {
+            total: self.total,
+            increment,
+        })?;
+        Ok(self.total)
+    }
+}
+
+/// Time between consecutive commits, in milliseconds.
+///
+/// The batch is expected to be in commit order (see [`sort_subdag_batch`]). A commit whose
+/// timestamp is lower than its predecessor's yields an interval of 0. Batches with fewer than
+/// two subdags yield no intervals.
+pub fn commit_intervals_ms(subdags: &[CommittedSubDag]) -> Vec<u64> {
+    subdags
+        .windows(2)
+        .map(|pair| pair[1].timestamp_ms.saturating_sub(pair[0].timestamp_ms))
+        .collect()
+}
+
+/// Drop subdags whose `commit_ref` was already seen earlier in the batch.
+///
+/// The first occurrence of each commit is kept and the input order is preserved.
+pub fn dedup_subdag_batch(subdags: Vec<CommittedSubDag>) -> Vec<CommittedSubDag> {
+    let mut seen = HashSet::new();
+    subdags
+        .into_iter()
+        .filter(|subdag| seen.insert(subdag.commit_ref))
+        .collect()
+}
+
+/// Transactions per second over a batch: the total transaction count of all subdags divided by
+/// the time between the first and last commit.
+///
+/// The batch is expected to be in commit order (see [`sort_subdag_batch`]). Returns `None` for
+/// fewer than two subdags or when the last timestamp is not after the first one.
+pub fn transaction_rate(subdags: &[CommittedSubDag]) -> Option<f64> {
+    let (first, last) = match subdags {
+        [first, .., last] => (first, last),
+        _ => return None,
+    };
+    let span_ms = last.timestamp_ms.saturating_sub(first.timestamp_ms);
+    if span_ms == 0 {
+        return None;
+    }
+    let total: usize = subdags.iter().map(CommittedSubDag::len).sum();
+    Some(total as f64 / (span_ms as f64 / 1000.0))
+}
+
+/// Lowest commit round of a batch, or `None` if the batch is empty.
+pub fn batch_first_round(subdags: &[CommittedSubDag]) -> Option<u64> {
+    subdags
+        .iter()
+        .map(|subdag| subdag.commit_ref.round as u64)
+        .min()
+}
+
+/// Highest commit round of a batch, or `None` if the batch is empty.
+pub fn batch_last_round(subdags: &[CommittedSubDag]) -> Option<u64> {
+    subdags
+        .iter()
+        .map(|subdag| subdag.commit_ref.round as u64)
+        .max()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::{BlockRef, CommitRef, Transaction};
+    use crate::{BlockDigest, SignedBlock, VerifiedBlock};
+
+    fn create_test_block_ref(round: u64) -> BlockRef {
+        let mut digest = [0u8; 32];
+        digest[0] = round as u8;
+        BlockRef {
+            digest,
+            round,
+            leader_address: String::new(),
+        }
+    }
+
+    fn create_test_commit_ref(round: usize) -> CommitRef {
+        let mut digest = [0u8; 32];
+        digest[0] = round as u8;
+        CommitRef { digest, round }
+    }
+
+    fn create_test_subdag(round: usize, transactions: Vec<Transaction>) -> CommittedSubDag {
+        let mut digest = [0u8; 32];
+        digest[0] = round as u8;
+        CommittedSubDag {
+            leader: create_test_block_ref(round as u64),
+            blocks: vec![VerifiedBlock {
+                block: SignedBlock::new(transactions),
+                digest: BlockDigest(digest),
+            }],
+            timestamp_ms: 1000 * round as u64,
+            commit_ref: create_test_commit_ref(round),
+            reputation_scores_desc: vec![],
+        }
+    }
+
+    #[test]
+    fn test_iter_batch_transactions() {
+        let subdags = vec![
+            create_test_subdag(
+                1,
+                vec![Transaction::new(vec![1]), Transaction::new(vec![2])],
+            ),
+            create_test_subdag(2, vec![Transaction::new(vec![3])]),
+        ];
+        let tagged: Vec<_> = iter_batch_transactions(&subdags)
+            .map(|(commit_ref, tx)| (commit_ref.round, tx.data().to_vec()))
+            .collect();
+        assert_eq!(tagged, vec![(1, vec![1]), (1, vec![2]), (2, vec![3])]);
+    }
+
+    #[test]
+    fn test_sort_subdag_batch() {
+        let mut same_round = create_test_subdag(2, vec![Transaction::new(vec![9])]);
+        same_round.timestamp_ms = 1;
+        let subdags = vec![
+            create_test_subdag(3, vec![]),
+            create_test_subdag(1, vec![]),
+            create_test_subdag(2, vec![]),
+            same_round,
+        ];
+        let sorted = sort_subdag_batch(subdags);
+        let rounds: Vec<_> = sorted.iter().map(|s| s.commit_ref.round).collect();
+        assert_eq!(rounds, vec![1, 2, 2, 3]);
+        assert_eq!(sorted[1].timestamp_ms, 2000);
+        assert_eq!(sorted[2].timestamp_ms, 1);
+    }
+
+    #[test]
+    fn test_interleave_by_round() {
+        let mut a = create_test_subdag(
+            1,
+            vec![Transaction::new(vec![1]), Transaction::new(vec![2])],
+        );
+        a.blocks.push(VerifiedBlock {
+            block: SignedBlock::new(vec![Transaction::new(vec![3])]),
+            digest: BlockDigest::default(),
+        });
+        a.blocks.push(VerifiedBlock {
+            block: SignedBlock::new(vec![Transaction::new(vec![4])]),
+            digest: BlockDigest::default(),
+        });
+        let mut b = create_test_subdag(2, vec![Transaction::new(vec![10])]);
+        b.blocks.push(VerifiedBlock {
+            block: SignedBlock::new(vec![Transaction::new(vec![20])]),
+            digest: BlockDigest::default(),
+        });
+        let interleaved: Vec<_> = interleave_by_round(&a, &b)
+            .into_iter()
+            .map(|tx| tx.data()[0])
+            .collect();
+        assert_eq!(interleaved, vec![1, 2, 10, 3, 20, 4]);
+    }
+
+    #[test]
+    fn test_throughput_by_round() {
+        let subdags = vec![
+            create_test_subdag(
+                5,
+                vec![Transaction::new(vec![1]), Transaction::new(vec![2])],
+            ),
+            create_test_subdag(3, vec![]),
+            create_test_subdag(7, vec![Transaction::new(vec![3])]),
+        ];
+        assert_eq!(throughput_by_round(&subdags), vec![(5, 2), (3, 0), (7, 1)]);
+    }
+
+    #[test]
+    fn test_subdag_batch_serialization() {
+        let subdags = vec![
+            create_test_subdag(1, vec![Transaction::new(vec![1, 2, 3])]),
+            create_test_subdag(2, vec![]),
+            create_test_subdag(
+                3,
+                vec![Transaction::new(vec![4]), Transaction::new(vec![5])],
+            ),
+        ];
+        let json = serialize_subdag_batch(&subdags).unwrap();
+        assert!(json.starts_with('['));
+        let decoded = deserialize_subdag_batch(&json).unwrap();
+        assert_eq!(decoded.len(), 3);
+        for (orig, deser) in subdags.iter().zip(decoded.iter()) {
+            assert_eq!(orig.commit_ref, deser.commit_ref);
+            assert_eq!(orig.flatten_transactions(), deser.flatten_transactions());
+        }
+    }
+
+    #[test]
+    fn test_cumulative_counter() {
+        let mut counter = CumulativeCounter::new();
+        let subdag1 = create_test_subdag(
+            1,
+            vec![Transaction::new(vec![1]), Transaction::new(vec![2])],
+        );
+        let subdag2 = create_test_subdag(2, vec![Transaction::new(vec![3])]);
+        assert_eq!(counter.add(&subdag1), Ok(2));
+        assert_eq!(counter.add(&subdag2), Ok(3));
+        assert_eq!(counter.total(), 3);
+    }
+
+    #[test]
+    fn test_cumulative_counter_overflow() {
+        let subdag = create_test_subdag(
+            1,
+            vec![Transaction::new(vec![1]), Transaction::new(vec![2])],
+        );
+        let mut counter = CumulativeCounter::from_total(u64::MAX - 2);
+        assert_eq!(counter.add(&subdag), Ok(u64::MAX));
+        assert_eq!(
+            counter.add(&subdag),
+            Err(CounterOverflow {
+                total: u64::MAX,
+                increment: 2
+            })
+        );
+        assert_eq!(counter.total(), u64::MAX);
+    }
+
+    #[test]
+    fn test_commit_intervals_ms_steady() {
+        let subdags: Vec<_> = (1..=4)
+            .map(|round| create_test_subdag(round, vec![]))
+            .collect();
+        assert_eq!(commit_intervals_ms(&subdags), vec![1000, 1000, 1000]);
+        assert!(commit_intervals_ms(&subdags[..1]).is_empty());
+    }
+
+    #[test]
+    fn test_commit_intervals_ms_irregular() {
+        let mut subdags: Vec<_> = (1..=4)
+            .map(|round| create_test_subdag(round, vec![]))
+            .collect();
+        subdags[1].timestamp_ms = 1500;
+        subdags[2].timestamp_ms = 1200;
+        subdags[3].timestamp_ms = 5000;
+        assert_eq!(commit_intervals_ms(&subdags), vec![500, 0, 3800]);
+    }
+
+    #[test]
+    fn test_dedup_subdag_batch() {
+        let mut duplicate = create_test_subdag(1, vec![Transaction::new(vec![9])]);
+        duplicate.timestamp_ms = 42;
+        let subdags = vec![
+            create_test_subdag(3, vec![]),
+            create_test_subdag(1, vec![Transaction::new(vec![1])]),
+            duplicate,
+            create_test_subdag(2, vec![]),
+            create_test_subdag(3, vec![]),
+        ];
+        let deduped = dedup_subdag_batch(subdags);
+        let rounds: Vec<_> = deduped.iter().map(|s| s.commit_ref.round).collect();
+        assert_eq!(rounds, vec![3, 1, 2]);
+        assert_eq!(deduped[1].timestamp_ms, 1000);
+    }
+
+    #[test]
+    fn test_dedup_subdag_batch_unique() {
+        let subdags: Vec<_> = (1..=3)
+            .map(|round| create_test_subdag(round, vec![]))
+            .collect();
+        assert_eq!(dedup_subdag_batch(subdags.clone()).len(), subdags.len());
+        assert!(dedup_subdag_batch(vec![]).is_empty());
+    }
+
+    #[test]
+    fn test_transaction_rate_steady() {
+        let subdags: Vec<_> = (1..=5)
+            .map(|round| {
+                create_test_subdag(
+                    round,
+                    vec![Transaction::new(vec![1]), Transaction::new(vec![2])],
+                )
+            })
+            .collect();
+        assert_eq!(transaction_rate(&subdags), Some(2.5));
+    }
+
+    #[test]
+    fn test_transaction_rate_insufficient_data() {
+        let single = vec![create_test_subdag(1, vec![Transaction::new(vec![1])])];
+        assert_eq!(transaction_rate(&single), None);
+        assert_eq!(transaction_rate(&[]), None);
+        let mut same_time = vec![create_test_subdag(1, vec![]), create_test_subdag(2, vec![])];
+        same_time[1].timestamp_ms = same_time[0].timestamp_ms;
+        assert_eq!(transaction_rate(&same_time), None);
+    }
+
+    #[test]
+    fn test_batch_rounds() {
+        let ordered: Vec<_> = (3..=6)
+            .map(|round| create_test_subdag(round, vec![]))
+            .collect();
+        assert_eq!(batch_first_round(&ordered), Some(3));
+        assert_eq!(batch_last_round(&ordered), Some(6));
+
+        let unordered: Vec<_> = [7, 2, 9, 4]
+            .into_iter()
+            .map(|round| create_test_subdag(round, vec![]))
+            .collect();
+        assert_eq!(batch_first_round(&unordered), Some(2));
+        assert_eq!(batch_last_round(&unordered), Some(9));
+
+        assert_eq!(batch_first_round(&[]), None);
+        assert_eq!(batch_last_round(&[]), None);
+    }
+}
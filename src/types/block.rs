@@ -11,7 +11,7 @@ pub type Block = Vec<Transaction>;
 ///
 /// Note: `BlockDigest` is computed over this struct, so any added field (without `#[serde(skip)]`)
 /// will affect the values of `BlockDigest` and `BlockRef`.
-#[derive(Debug, Clone, Deserialize, Serialize, Default)]
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize, Default)]
 pub struct SignedBlock {
     inner: Block,
     signature: Vec<u8>,
@@ -25,11 +25,73 @@ impl SignedBlock {
         }
     }
 
+    /// Create a block carrying the given signature
+    pub fn with_signature(block: Block, signature: Vec<u8>) -> Self {
+        Self {
+            inner: block,
+            signature,
+        }
+    }
+
+    /// Create an unsigned block from raw transaction bytes, without collecting them first
+    pub fn from_iter_bytes<I: IntoIterator<Item = Vec<u8>>>(iter: I) -> SignedBlock {
+        Self::new(iter.into_iter().map(Transaction::new).collect())
+    }
+
     /// Get a reference to the transactions in this block
     pub fn transactions(&self) -> &Block {
         &self.inner
     }
 
+    /// Consume the block and return its transactions, dropping the signature
+    pub fn into_transactions(self) -> Block {
+        self.inner
+    }
+
+    /// Append a transaction to the block.
+    ///
+    /// The signature is left untouched, so it no longer covers the block contents.
+    pub(crate) fn push_transaction(&mut self, tx: Transaction) {
+        self.inner.push(tx);
+    }
+
+    /// Get the signature bytes of this block
+    pub fn signature(&self) -> &[u8] {
+        &self.signature
+    }
+
+    /// Whether the block carries a (non-empty) signature
+    pub fn is_signed(&self) -> bool {
+        !self.signature.is_empty()
+    }
+
+    /// Serialize only the transactions of this block, as a JSON `Block`.
+    ///
+    /// The signature is dropped, so the result cannot be used to reproduce the block's
+    /// `BlockDigest`.
+    pub fn to_unsigned_json(&self) -> Result<String, serde_json::Error> {
+        serde_json::to_string(&self.inner)
+    }
+
+    /// Compute the `BlockDigest` of this block: SHA-256 over its JSON serialization with object
+    /// keys sorted, so the digest doesn't depend on field order or serde_json features.
+    #[cfg(feature = "sha2")]
+    pub fn compute_digest(&self) -> BlockDigest {
+        let bytes = crate::types::to_canonical_json_vec(self)
+            .expect("SignedBlock serialization cannot fail");
+        BlockDigest::hash_bytes(&bytes)
+    }
+
+    /// Wrap this block into a `VerifiedBlock`, computing its digest.
+    #[cfg(feature = "sha2")]
+    pub fn into_verified(self) -> crate::VerifiedBlock {
+        let digest = self.compute_digest();
+        crate::VerifiedBlock {
+            block: self,
+            digest,
+        }
+    }
+
     /// Clears signature for testing.
     #[cfg(test)]
     pub(crate) fn clear_signature(&mut self) {
@@ -49,6 +111,13 @@ impl BlockDigest {
     /// Lexicographic min & max digest.
     pub const MIN: Self = Self([u8::MIN; DIGEST_LENGTH]);
     pub const MAX: Self = Self([u8::MAX; DIGEST_LENGTH]);
+
+    /// SHA-256 digest of arbitrary bytes, using the same hash as `SignedBlock::compute_digest`.
+    #[cfg(feature = "sha2")]
+    pub fn hash_bytes(data: &[u8]) -> BlockDigest {
+        use sha2::{Digest, Sha256};
+        Self(Sha256::digest(data).into())
+    }
 }
 
 impl Hash for BlockDigest {
@@ -158,6 +227,59 @@ mod tests {
         assert_eq!(block1.transactions().len(), block2.transactions().len());
     }
 
+    #[test]
+    fn test_signed_block_with_signature() {
+        let transactions = vec![Transaction::new(vec![1, 2, 3])];
+        let block = SignedBlock::with_signature(transactions.clone(), vec![9, 9]);
+        assert_eq!(block.transactions(), &transactions);
+        assert_eq!(block.signature(), &[9, 9]);
+        assert!(block.is_signed());
+        assert!(!SignedBlock::new(transactions).is_signed());
+    }
+
+    #[cfg(feature = "sha2")]
+    #[test]
+    fn test_signed_block_compute_digest() {
+        let block = SignedBlock::with_signature(vec![Transaction::new(vec![1, 2, 3])], vec![7]);
+        assert_eq!(block.compute_digest(), block.clone().compute_digest());
+        let unsigned = SignedBlock::new(vec![Transaction::new(vec![1, 2, 3])]);
+        assert_ne!(block.compute_digest(), unsigned.compute_digest());
+    }
+
+    #[cfg(feature = "sha2")]
+    #[test]
+    fn test_signed_block_compute_digest_canonical() {
+        let block = SignedBlock::with_signature(vec![Transaction::new(vec![1, 2, 3])], vec![7]);
+        let canonical = crate::types::to_canonical_json_vec(&block).unwrap();
+        assert_eq!(
+            canonical,
+            br#"{"inner":[{"inner":[1,2,3]}],"signature":[7]}"#.to_vec()
+        );
+        assert_eq!(block.compute_digest(), BlockDigest::hash_bytes(&canonical));
+    }
+
+    #[test]
+    fn test_signed_block_to_unsigned_json() {
+        let transactions = vec![
+            Transaction::new(vec![1, 2, 3]),
+            Transaction::new(vec![4, 5, 6]),
+        ];
+        let block = SignedBlock::with_signature(transactions.clone(), vec![42; 8]);
+        let json = block.to_unsigned_json().unwrap();
+        assert!(!json.contains("signature"));
+        let decoded: Block = serde_json::from_str(&json).unwrap();
+        assert_eq!(decoded, transactions);
+    }
+
+    #[cfg(feature = "sha2")]
+    #[test]
+    fn test_signed_block_into_verified() {
+        let block = SignedBlock::with_signature(vec![Transaction::new(vec![1, 2, 3])], vec![7]);
+        let verified = block.clone().into_verified();
+        assert_eq!(verified.block, block);
+        assert_eq!(verified.digest, verified.block.compute_digest());
+    }
+
     #[test]
     fn test_block_digest_default() {
         let digest = BlockDigest::default();
@@ -215,6 +337,24 @@ mod tests {
         assert_eq!(hasher1.finish(), hasher2.finish());
     }
 
+    #[cfg(feature = "sha2")]
+    #[test]
+    fn test_block_digest_hash_bytes() {
+        assert_eq!(
+            BlockDigest::hash_bytes(b"hello"),
+            BlockDigest::hash_bytes(b"hello")
+        );
+        assert_ne!(
+            BlockDigest::hash_bytes(b"hello"),
+            BlockDigest::hash_bytes(b"world")
+        );
+        // SHA-256 of the empty string
+        assert_eq!(
+            BlockDigest::hash_bytes(&[]).0[..4],
+            [0xe3, 0xb0, 0xc4, 0x42]
+        );
+    }
+
     #[test]
     fn test_block_digest_as_ref() {
         let mut digest = [0u8; DIGEST_LENGTH];
@@ -253,4 +393,12 @@ mod tests {
         // Should display full base64 encoding
         assert!(!debug_str.is_empty());
     }
+
+    #[test]
+    fn test_signed_block_from_iter_bytes() {
+        let block = SignedBlock::from_iter_bytes((0u8..5).map(|i| vec![i; i as usize + 1]));
+        assert_eq!(block.transactions().len(), 5);
+        assert_eq!(block.transactions()[2], Transaction::new(vec![2, 2, 2]));
+        assert!(!block.is_signed());
+    }
 }
@@ -0,0 +1,97 @@
+//! Hash chain linking consecutive commits.
+
+use sha2::{Digest, Sha256};
+
+use crate::types::DIGEST_LENGTH;
+use crate::CommittedSubDag;
+
+/// Head value of a `CommitChain` before any commit has been added.
+pub const GENESIS_CHAIN_HEAD: [u8; DIGEST_LENGTH] = [0u8; DIGEST_LENGTH];
+
+/// A running hash chain over a sequence of commits.
+///
+/// Each new head is `SHA-256(previous_head || commit_hash)`, starting from
+/// [`GENESIS_CHAIN_HEAD`]. Two chains have the same head only if they were extended with the
+/// same commits in the same order.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CommitChain {
+    head: [u8; DIGEST_LENGTH],
+}
+
+impl Default for CommitChain {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl CommitChain {
+    /// Create a chain starting at the genesis head
+    pub fn new() -> Self {
+        Self {
+            head: GENESIS_CHAIN_HEAD,
+        }
+    }
+
+    /// Get the current chain head
+    pub fn head(&self) -> [u8; DIGEST_LENGTH] {
+        self.head
+    }
+
+    /// Append a commit to the chain and return the new head.
+    pub fn extend(&mut self, subdag: &CommittedSubDag) -> [u8; DIGEST_LENGTH] {
+        let mut hasher = Sha256::new();
+        hasher.update(self.head);
+        hasher.update(subdag.commit_hash());
+        self.head = hasher.finalize().into();
+        self.head
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::CommitRef;
+
+    fn create_test_subdag(round: usize) -> CommittedSubDag {
+        let mut digest = [0u8; DIGEST_LENGTH];
+        digest[0] = round as u8;
+        CommittedSubDag {
+            commit_ref: CommitRef { digest, round },
+            timestamp_ms: 1000 * round as u64,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_commit_chain_genesis() {
+        assert_eq!(CommitChain::new().head(), GENESIS_CHAIN_HEAD);
+    }
+
+    #[test]
+    fn test_commit_chain_extend_deterministic() {
+        let subdags: Vec<_> = (1..=3).map(create_test_subdag).collect();
+        let mut chain1 = CommitChain::new();
+        let mut chain2 = CommitChain::new();
+        let mut heads = vec![chain1.head()];
+        for subdag in &subdags {
+            let head = chain1.extend(subdag);
+            assert_eq!(head, chain1.head());
+            assert_eq!(head, chain2.extend(subdag));
+            assert!(!heads.contains(&head));
+            heads.push(head);
+        }
+    }
+
+    #[test]
+    fn test_commit_chain_order_matters() {
+        let subdag1 = create_test_subdag(1);
+        let subdag2 = create_test_subdag(2);
+        let mut chain1 = CommitChain::new();
+        chain1.extend(&subdag1);
+        chain1.extend(&subdag2);
+        let mut chain2 = CommitChain::new();
+        chain2.extend(&subdag2);
+        chain2.extend(&subdag1);
+        assert_ne!(chain1.head(), chain2.head());
+    }
+}
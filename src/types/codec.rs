@@ -0,0 +1,260 @@
+//! CBOR encoding for committed subdags.
+//!
+//! Byte fields (transactions, signatures and digests) are encoded as CBOR byte strings rather
+//! than arrays of integers, which keeps the encoding compact. This only applies to the CBOR
+//! path: the subdag is converted to private mirror types carrying the byte-string hints, so the
+//! serde representation of the public types, and thus their encoding in other formats, is left
+//! untouched.
+
+use std::borrow::Cow;
+
+use serde::{Deserialize, Deserializer, Serialize};
+
+use crate::types::DIGEST_LENGTH;
+use crate::types::{AuthorityIndex, BlockRef, BlockTimestampMs, CommitRef, Transaction};
+use crate::{BlockDigest, CommittedSubDag, SignedBlock, VerifiedBlock};
+
+/// Encode a subdag as CBOR.
+pub fn to_cbor(subdag: &CommittedSubDag) -> Result<Vec<u8>, ciborium::ser::Error<std::io::Error>> {
+    let mut bytes = Vec::new();
+    ciborium::into_writer(&CborSubDag::from(subdag), &mut bytes)?;
+    Ok(bytes)
+}
+
+/// Decode a subdag from CBOR produced by [`to_cbor`].
+pub fn from_cbor(bytes: &[u8]) -> Result<CommittedSubDag, ciborium::de::Error<std::io::Error>> {
+    let subdag: CborSubDag<'static> = ciborium::from_reader(bytes)?;
+    Ok(subdag.into())
+}
+
+// Mirrors of the public types, with the same field names and order, borrowing the payloads when
+// encoding.
+
+#[derive(Serialize, Deserialize)]
+struct CborSubDag<'a> {
+    leader: CborBlockRef<'a>,
+    blocks: Vec<CborVerifiedBlock<'a>>,
+    timestamp_ms: BlockTimestampMs,
+    commit_ref: CborCommitRef,
+    reputation_scores_desc: Cow<'a, [(AuthorityIndex, u64)]>,
+}
+
+#[derive(Serialize, Deserialize)]
+struct CborBlockRef<'a> {
+    leader_address: Cow<'a, str>,
+    #[serde(with = "serde_bytes")]
+    digest: [u8; DIGEST_LENGTH],
+    round: u64,
+}
+
+#[derive(Serialize, Deserialize)]
+struct CborCommitRef {
+    #[serde(with = "serde_bytes")]
+    digest: [u8; DIGEST_LENGTH],
+    round: usize,
+}
+
+#[derive(Serialize, Deserialize)]
+struct CborVerifiedBlock<'a> {
+    block: CborSignedBlock<'a>,
+    digest: CborBlockDigest,
+}
+
+#[derive(Serialize, Deserialize)]
+struct CborSignedBlock<'a> {
+    inner: Vec<CborTransaction<'a>>,
+    #[serde(
+        serialize_with = "serde_bytes::serialize",
+        deserialize_with = "deserialize_owned_bytes"
+    )]
+    signature: Cow<'a, [u8]>,
+}
+
+#[derive(Serialize, Deserialize)]
+struct CborTransaction<'a> {
+    #[serde(
+        serialize_with = "serde_bytes::serialize",
+        deserialize_with = "deserialize_owned_bytes"
+    )]
+    inner: Cow<'a, [u8]>,
+}
+
+#[derive(Serialize, Deserialize)]
+struct CborBlockDigest(#[serde(with = "serde_bytes")] [u8; DIGEST_LENGTH]);
+
+/// Decode a byte string into an owned buffer, so decoding does not borrow from the input.
+fn deserialize_owned_bytes<'de, 'a, D: Deserializer<'de>>(
+    deserializer: D,
+) -> Result<Cow<'a, [u8]>, D::Error> {
+    serde_bytes::ByteBuf::deserialize(deserializer).map(|bytes| Cow::Owned(bytes.into_vec()))
+}
+
+impl<'a> From<&'a CommittedSubDag> for CborSubDag<'a> {
+    fn from(subdag: &'a CommittedSubDag) -> Self {
+        Self {
+            leader: CborBlockRef {
+                leader_address: Cow::Borrowed(&subdag.leader.leader_address),
+                digest: subdag.leader.digest,
+                round: subdag.leader.round,
+            },
+            blocks: subdag
+                .blocks
+                .iter()
+                .map(|block| CborVerifiedBlock {
+                    block: CborSignedBlock {
+                        inner: block
+                            .block
+                            .transactions()
+                            .iter()
+                            .map(|tx| CborTransaction {
+                                inner: Cow::Borrowed(tx.data()),
+                            })
+                            .collect(),
+                        signature: Cow::Borrowed(block.block.signature()),
+                    },
+                    digest: CborBlockDigest(block.digest.0),
+                })
+                .collect(),
+            timestamp_ms: subdag.timestamp_ms,
+            commit_ref: CborCommitRef {
+                digest: subdag.commit_ref.digest,
+                round: subdag.commit_ref.round,
+            },
+            reputation_scores_desc: Cow::Borrowed(&subdag.reputation_scores_desc),
+        }
+    }
+}
+
+impl From<CborSubDag<'_>> for CommittedSubDag {
+    fn from(subdag: CborSubDag<'_>) -> Self {
+        Self {
+            leader: BlockRef {
+                leader_address: subdag.leader.leader_address.into_owned(),
+                digest: subdag.leader.digest,
+                round: subdag.leader.round,
+            },
+            blocks: subdag
+                .blocks
+                .into_iter()
+                .map(|block| VerifiedBlock {
+                    block: SignedBlock::with_signature(
+                        block
+                            .block
+                            .inner
+                            .into_iter()
+                            .map(|tx| Transaction::new(tx.inner.into_owned()))
+                            .collect(),
+                        block.block.signature.into_owned(),
+                    ),
+                    digest: BlockDigest(block.digest.0),
+                })
+                .collect(),
+            timestamp_ms: subdag.timestamp_ms,
+            commit_ref: CommitRef {
+                digest: subdag.commit_ref.digest,
+                round: subdag.commit_ref.round,
+            },
+            reputation_scores_desc: subdag.reputation_scores_desc.into_owned(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ciborium::Value;
+
+    fn create_test_committed_subdag() -> CommittedSubDag {
+        let mut digest = [0u8; 32];
+        digest[0] = 1;
+        CommittedSubDag {
+            leader: BlockRef {
+                digest,
+                round: 1,
+                leader_address: String::new(),
+            },
+            blocks: vec![VerifiedBlock {
+                block: SignedBlock::new(vec![
+                    Transaction::new(vec![0xff; 64]),
+                    Transaction::new(vec![1, 2, 3]),
+                ]),
+                digest: BlockDigest(digest),
+            }],
+            timestamp_ms: 1000,
+            commit_ref: CommitRef { digest, round: 1 },
+            reputation_scores_desc: vec![(0, 100), (1, 90)],
+        }
+    }
+
+    #[test]
+    fn test_cbor_round_trip() {
+        let subdag = create_test_committed_subdag();
+        let bytes = to_cbor(&subdag).unwrap();
+        let decoded = from_cbor(&bytes).unwrap();
+        assert_eq!(subdag.leader, decoded.leader);
+        assert_eq!(subdag.commit_ref, decoded.commit_ref);
+        assert_eq!(subdag.timestamp_ms, decoded.timestamp_ms);
+        assert_eq!(subdag.blocks[0].digest, decoded.blocks[0].digest);
+        assert_eq!(
+            subdag.flatten_transactions(),
+            decoded.flatten_transactions()
+        );
+        assert_eq!(
+            subdag.reputation_scores_desc,
+            decoded.reputation_scores_desc
+        );
+    }
+
+    #[test]
+    fn test_cbor_smaller_than_json() {
+        let subdag = create_test_committed_subdag();
+        let cbor = to_cbor(&subdag).unwrap();
+        let json = serde_json::to_vec(&subdag).unwrap();
+        assert!(cbor.len() < json.len());
+    }
+
+    fn field<'a>(value: &'a Value, name: &str) -> &'a Value {
+        value
+            .as_map()
+            .unwrap()
+            .iter()
+            .find(|(key, _)| key.as_text() == Some(name))
+            .map(|(_, value)| value)
+            .unwrap()
+    }
+
+    #[test]
+    fn test_cbor_round_trip_signed() {
+        let mut subdag = create_test_committed_subdag();
+        subdag.blocks[0].block =
+            SignedBlock::with_signature(vec![Transaction::new(vec![7])], vec![0xaa; 64]);
+        let decoded = from_cbor(&to_cbor(&subdag).unwrap()).unwrap();
+        assert_eq!(decoded.blocks, subdag.blocks);
+    }
+
+    #[test]
+    fn test_cbor_encodes_byte_strings() {
+        let subdag = create_test_committed_subdag();
+        let bytes = to_cbor(&subdag).unwrap();
+        let value: Value = ciborium::from_reader(bytes.as_slice()).unwrap();
+        let block = &field(&value, "blocks").as_array().unwrap()[0];
+        let tx = &field(field(block, "block"), "inner").as_array().unwrap()[1];
+        assert_eq!(field(tx, "inner"), &Value::Bytes(vec![1, 2, 3]));
+        assert!(field(block, "digest").is_bytes());
+        assert!(field(field(&value, "commit_ref"), "digest").is_bytes());
+    }
+
+    #[test]
+    fn test_public_types_keep_default_encoding() {
+        // The byte-string hints are local to the codec; the public types keep serde's default
+        // representation, i.e. a sequence of integers.
+        let mut bytes = Vec::new();
+        ciborium::into_writer(&Transaction::new(vec![1, 2, 3]), &mut bytes).unwrap();
+        let value: Value = ciborium::from_reader(bytes.as_slice()).unwrap();
+        assert!(field(&value, "inner").is_array());
+        bytes.clear();
+        ciborium::into_writer(&BlockDigest([1; 32]), &mut bytes).unwrap();
+        let value: Value = ciborium::from_reader(bytes.as_slice()).unwrap();
+        assert_eq!(value.as_array().unwrap().len(), 32);
+    }
+}
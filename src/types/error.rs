@@ -0,0 +1,172 @@
+//! Error types returned by the helpers in this crate.
+
+use std::fmt;
+
+use crate::BlockDigest;
+
+/// Error returned when parsing a digest from its hex representation.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DigestParseError {
+    /// The input is not a valid hex string
+    InvalidHex,
+    /// The decoded digest does not have `DIGEST_LENGTH` bytes
+    InvalidLength(usize),
+    /// The round does not fit into the target round type
+    InvalidRound(u64),
+}
+
+impl fmt::Display for DigestParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::InvalidHex => write!(f, "digest is not a valid hex string"),
+            Self::InvalidLength(len) => write!(f, "invalid digest length: {len} bytes"),
+            Self::InvalidRound(round) => write!(f, "round {round} is out of range"),
+        }
+    }
+}
+
+impl std::error::Error for DigestParseError {}
+
+/// Error returned when decoding `CommitMetadata` from its compact binary form.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum MetadataDecodeError {
+    /// The input ended before all fields were read
+    Truncated,
+    /// The leader address is not valid UTF-8
+    InvalidLeaderAddress,
+    /// The commit round does not fit into `usize`
+    InvalidRound(u64),
+    /// Bytes were left over after decoding
+    TrailingBytes(usize),
+}
+
+impl fmt::Display for MetadataDecodeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Truncated => write!(f, "compact metadata is truncated"),
+            Self::InvalidLeaderAddress => write!(f, "leader address is not valid UTF-8"),
+            Self::InvalidRound(round) => write!(f, "commit round {round} is out of range"),
+            Self::TrailingBytes(len) => write!(f, "{len} trailing bytes after metadata"),
+        }
+    }
+}
+
+impl std::error::Error for MetadataDecodeError {}
+
+/// Error returned when a `CumulativeCounter` would exceed `u64::MAX`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CounterOverflow {
+    /// Counter value before the failed addition
+    pub total: u64,
+    /// Amount that could not be added
+    pub increment: u64,
+}
+
+impl fmt::Display for CounterOverflow {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "adding {} to counter at {} overflows u64",
+            self.increment, self.total
+        )
+    }
+}
+
+impl std::error::Error for CounterOverflow {}
+
+/// Error returned when parsing a `BlockRef` from its string identifier.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ParseError {
+    /// The identifier does not have exactly three `:`-separated components
+    InvalidFormat,
+    /// The leader address is neither empty nor a `0x`-prefixed 20-byte hex string
+    InvalidLeaderAddress(String),
+    /// The round is not a valid `u64`
+    InvalidRound(String),
+    /// The digest is not a valid hex encoded digest
+    InvalidDigest(DigestParseError),
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::InvalidFormat => write!(f, "expected `{{leader_address}}:{{round}}:{{digest}}`"),
+            Self::InvalidLeaderAddress(addr) => write!(f, "invalid leader address: {addr}"),
+            Self::InvalidRound(round) => write!(f, "invalid round: {round}"),
+            Self::InvalidDigest(err) => write!(f, "invalid digest: {err}"),
+        }
+    }
+}
+
+impl std::error::Error for ParseError {}
+
+/// Error returned when an operation needs a block but the subdag has none.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct NoBlocksError;
+
+impl fmt::Display for NoBlocksError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "subdag has no blocks")
+    }
+}
+
+impl std::error::Error for NoBlocksError {}
+
+/// Error returned when a subdag does not carry the expected number of transactions.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CountMismatch {
+    /// Number of transactions that was expected
+    pub expected: usize,
+    /// Number of transactions actually in the subdag
+    pub actual: usize,
+}
+
+impl fmt::Display for CountMismatch {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "expected {} transactions, found {}",
+            self.expected, self.actual
+        )
+    }
+}
+
+impl std::error::Error for CountMismatch {}
+
+/// Error returned when a transaction exceeds the allowed size.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct OversizedTransaction {
+    /// Position of the transaction in flatten order
+    pub index: usize,
+    /// Byte length of the transaction
+    pub size: usize,
+    /// Maximum allowed byte length
+    pub max_bytes: usize,
+}
+
+impl fmt::Display for OversizedTransaction {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "transaction {} is {} bytes, exceeding the limit of {} bytes",
+            self.index, self.size, self.max_bytes
+        )
+    }
+}
+
+impl std::error::Error for OversizedTransaction {}
+
+/// Error returned when a block is required to carry transactions but has none.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct EmptyBlockError {
+    /// Digest of the empty block
+    pub digest: BlockDigest,
+}
+
+impl fmt::Display for EmptyBlockError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "block {} has no transactions", self.digest)
+    }
+}
+
+impl std::error::Error for EmptyBlockError {}
@@ -0,0 +1,413 @@
+//! Commit level metadata, i.e. a `CommittedSubDag` without its block payloads.
+
+use serde::{Deserialize, Serialize};
+
+use crate::types::{
+    AuthorityIndex, BlockRef, BlockTimestampMs, CommitRef, MetadataDecodeError, DIGEST_LENGTH,
+};
+use crate::{BlockDigest, CommittedSubDag};
+
+/// Size of the fixed-size fields of [`CommitMetadata::to_compact_bytes`]: every field of the
+/// layout except the leader address bytes and the reputation entries.
+pub const COMPACT_METADATA_HEADER_SIZE: usize = 8 + DIGEST_LENGTH + 8 + 8 + DIGEST_LENGTH + 4 + 4;
+
+/// Size of one reputation entry in [`CommitMetadata::to_compact_bytes`].
+pub const COMPACT_REPUTATION_ENTRY_SIZE: usize = 4 + 8;
+
+/// Metadata of a committed subdag: everything except the blocks.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, Default)]
+pub struct CommitMetadata {
+    pub leader: BlockRef,
+    pub timestamp_ms: BlockTimestampMs,
+    pub commit_ref: CommitRef,
+    pub reputation_scores_desc: Vec<(AuthorityIndex, u64)>,
+}
+
+impl CommitMetadata {
+    /// Encode the metadata into a compact binary form.
+    ///
+    /// Layout (all integers little-endian):
+    ///
+    /// | field                   | size                        |
+    /// |-------------------------|-----------------------------|
+    /// | commit round            | 8 bytes (`u64`)             |
+    /// | commit digest           | 32 bytes                    |
+    /// | timestamp_ms            | 8 bytes (`u64`)             |
+    /// | leader round            | 8 bytes (`u64`)             |
+    /// | leader digest           | 32 bytes                    |
+    /// | leader address length   | 4 bytes (`u32`)             |
+    /// | leader address          | UTF-8 bytes                 |
+    /// | reputation entry count  | 4 bytes (`u32`)             |
+    /// | reputation entries      | 12 bytes each (`u32`, `u64`)|
+    ///
+    /// # Panics
+    ///
+    /// Panics if the leader address is longer than `u32::MAX` bytes or there are more than
+    /// `u32::MAX` reputation entries, as their lengths could not be encoded.
+    pub fn to_compact_bytes(&self) -> Vec<u8> {
+        let address = self.leader.leader_address.as_bytes();
+        let mut bytes = Vec::with_capacity(compact_metadata_len(
+            address.len(),
+            self.reputation_scores_desc.len(),
+        ));
+        bytes.extend_from_slice(&(self.commit_ref.round as u64).to_le_bytes());
+        bytes.extend_from_slice(&self.commit_ref.digest);
+        bytes.extend_from_slice(&self.timestamp_ms.to_le_bytes());
+        bytes.extend_from_slice(&self.leader.round.to_le_bytes());
+        bytes.extend_from_slice(&self.leader.digest);
+        let address_len = u32::try_from(address.len()).expect("leader address length exceeds u32");
+        bytes.extend_from_slice(&address_len.to_le_bytes());
+        bytes.extend_from_slice(address);
+        let entries = u32::try_from(self.reputation_scores_desc.len())
+            .expect("reputation entry count exceeds u32");
+        bytes.extend_from_slice(&entries.to_le_bytes());
+        for (authority, score) in &self.reputation_scores_desc {
+            bytes.extend_from_slice(&authority.to_le_bytes());
+            bytes.extend_from_slice(&score.to_le_bytes());
+        }
+        bytes
+    }
+
+    /// Decode metadata produced by [`CommitMetadata::to_compact_bytes`].
+    pub fn from_compact_bytes(bytes: &[u8]) -> Result<Self, MetadataDecodeError> {
+        let mut reader = CompactReader { bytes };
+        let commit_round = reader.read_u64()?;
+        let commit_round = usize::try_from(commit_round)
+            .map_err(|_| MetadataDecodeError::InvalidRound(commit_round))?;
+        let commit_digest = reader.read_digest()?;
+        let timestamp_ms = reader.read_u64()?;
+        let leader_round = reader.read_u64()?;
+        let leader_digest = reader.read_digest()?;
+        let address_len = reader.read_u32()? as usize;
+        let leader_address = String::from_utf8(reader.take(address_len)?.to_vec())
+            .map_err(|_| MetadataDecodeError::InvalidLeaderAddress)?;
+        let entries = reader.read_u32()? as usize;
+        let mut reputation_scores_desc =
+            Vec::with_capacity(entries.min(reader.bytes.len() / COMPACT_REPUTATION_ENTRY_SIZE));
+        for _ in 0..entries {
+            reputation_scores_desc.push((reader.read_u32()?, reader.read_u64()?));
+        }
+        if !reader.bytes.is_empty() {
+            return Err(MetadataDecodeError::TrailingBytes(reader.bytes.len()));
+        }
+        Ok(Self {
+            leader: BlockRef {
+                leader_address,
+                digest: leader_digest,
+                round: leader_round,
+            },
+            timestamp_ms,
+            commit_ref: CommitRef {
+                digest: commit_digest,
+                round: commit_round,
+            },
+            reputation_scores_desc,
+        })
+    }
+}
+
+/// A message carrying either a full subdag or only its metadata.
+///
+/// Serialized with an internal `type` tag, so one channel can carry both variants.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "type")]
+pub enum SubDagMessage {
+    Full(CommittedSubDag),
+    MetadataOnly(CommitMetadata),
+}
+
+/// Compact acknowledgment of a commit, enough for a client to confirm it without re-downloading
+/// the subdag.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CommitReceipt {
+    pub commit_ref: CommitRef,
+    pub transaction_count: usize,
+    /// `CommittedSubDag::commit_hash`, only computed when the `sha2` feature is enabled
+    pub commit_hash: Option<[u8; DIGEST_LENGTH]>,
+}
+
+/// Length of the compact encoding of metadata with a leader address of `address_len` bytes and
+/// `reputation_entries` reputation scores.
+fn compact_metadata_len(address_len: usize, reputation_entries: usize) -> usize {
+    COMPACT_METADATA_HEADER_SIZE + address_len + COMPACT_REPUTATION_ENTRY_SIZE * reputation_entries
+}
+
+struct CompactReader<'a> {
+    bytes: &'a [u8],
+}
+
+impl<'a> CompactReader<'a> {
+    fn take(&mut self, len: usize) -> Result<&'a [u8], MetadataDecodeError> {
+        if self.bytes.len() < len {
+            return Err(MetadataDecodeError::Truncated);
+        }
+        let (head, tail) = self.bytes.split_at(len);
+        self.bytes = tail;
+        Ok(head)
+    }
+
+    fn read_u32(&mut self) -> Result<u32, MetadataDecodeError> {
+        let bytes = self.take(4)?;
+        Ok(u32::from_le_bytes(bytes.try_into().unwrap()))
+    }
+
+    fn read_u64(&mut self) -> Result<u64, MetadataDecodeError> {
+        let bytes = self.take(8)?;
+        Ok(u64::from_le_bytes(bytes.try_into().unwrap()))
+    }
+
+    fn read_digest(&mut self) -> Result<[u8; DIGEST_LENGTH], MetadataDecodeError> {
+        let bytes = self.take(DIGEST_LENGTH)?;
+        Ok(bytes.try_into().unwrap())
+    }
+}
+
+impl CommittedSubDag {
+    /// Extract the commit metadata, leaving out the blocks.
+    pub fn metadata(&self) -> CommitMetadata {
+        CommitMetadata {
+            leader: self.leader.clone(),
+            timestamp_ms: self.timestamp_ms,
+            commit_ref: self.commit_ref,
+            reputation_scores_desc: self.reputation_scores_desc.clone(),
+        }
+    }
+
+    /// Length of `self.metadata().to_compact_bytes()`, computed without encoding it.
+    pub(crate) fn compact_metadata_len(&self) -> usize {
+        compact_metadata_len(
+            self.leader.leader_address.len(),
+            self.reputation_scores_desc.len(),
+        )
+    }
+
+    /// Build the receipt acknowledging this commit.
+    ///
+    /// The receipt's `commit_hash` is `None` unless the `sha2` feature is enabled.
+    pub fn receipt(&self) -> CommitReceipt {
+        #[cfg(feature = "sha2")]
+        let commit_hash = Some(self.commit_hash());
+        #[cfg(not(feature = "sha2"))]
+        let commit_hash = None;
+        CommitReceipt {
+            commit_ref: self.commit_ref,
+            transaction_count: self.len(),
+            commit_hash,
+        }
+    }
+
+    /// Serialize the commit metadata plus, for each block, its digest and transaction count.
+    ///
+    /// Raw transaction bytes and signatures are left out, so the summary cannot be turned back
+    /// into the full subdag; it is meant for tracking commit structure and fetching payloads
+    /// lazily.
+    pub fn serialize_summary(&self) -> Result<String, serde_json::Error> {
+        let summary = SubDagSummary {
+            metadata: self.metadata(),
+            blocks: self
+                .blocks
+                .iter()
+                .map(|block| BlockSummary {
+                    digest: block.digest,
+                    transaction_count: block.block.transactions().len(),
+                })
+                .collect(),
+        };
+        serde_json::to_string(&summary)
+    }
+}
+
+#[derive(Serialize)]
+struct SubDagSummary {
+    #[serde(flatten)]
+    metadata: CommitMetadata,
+    blocks: Vec<BlockSummary>,
+}
+
+#[derive(Serialize)]
+struct BlockSummary {
+    digest: BlockDigest,
+    transaction_count: usize,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn create_test_metadata() -> CommitMetadata {
+        let mut digest = [0u8; DIGEST_LENGTH];
+        digest[0] = 7;
+        CommitMetadata {
+            leader: BlockRef {
+                leader_address: "0x0000000000000000000000000000000000000001".to_string(),
+                digest,
+                round: 9,
+            },
+            timestamp_ms: 1234567890,
+            commit_ref: CommitRef { digest, round: 10 },
+            reputation_scores_desc: vec![(0, 100), (1, 90)],
+        }
+    }
+
+    #[test]
+    fn test_metadata_from_subdag() {
+        let metadata = create_test_metadata();
+        let subdag = CommittedSubDag {
+            leader: metadata.leader.clone(),
+            blocks: vec![],
+            timestamp_ms: metadata.timestamp_ms,
+            commit_ref: metadata.commit_ref,
+            reputation_scores_desc: metadata.reputation_scores_desc.clone(),
+        };
+        assert_eq!(subdag.metadata(), metadata);
+    }
+
+    #[test]
+    fn test_compact_bytes_round_trip() {
+        let metadata = create_test_metadata();
+        let bytes = metadata.to_compact_bytes();
+        assert_eq!(COMPACT_METADATA_HEADER_SIZE, 96);
+        assert_eq!(
+            bytes.len(),
+            COMPACT_METADATA_HEADER_SIZE + 42 + 2 * COMPACT_REPUTATION_ENTRY_SIZE
+        );
+        assert_eq!(CommitMetadata::from_compact_bytes(&bytes), Ok(metadata));
+    }
+
+    #[test]
+    fn test_compact_bytes_truncated() {
+        let bytes = create_test_metadata().to_compact_bytes();
+        assert_eq!(
+            CommitMetadata::from_compact_bytes(&bytes[..bytes.len() - 1]),
+            Err(MetadataDecodeError::Truncated)
+        );
+    }
+
+    #[test]
+    fn test_subdag_message_full_round_trip() {
+        let metadata = create_test_metadata();
+        let subdag = CommittedSubDag {
+            leader: metadata.leader.clone(),
+            blocks: vec![],
+            timestamp_ms: metadata.timestamp_ms,
+            commit_ref: metadata.commit_ref,
+            reputation_scores_desc: metadata.reputation_scores_desc.clone(),
+        };
+        let json = serde_json::to_string(&SubDagMessage::Full(subdag)).unwrap();
+        assert!(json.contains(r#""type":"Full""#));
+        match serde_json::from_str(&json).unwrap() {
+            SubDagMessage::Full(decoded) => assert_eq!(decoded.metadata(), metadata),
+            other => panic!("unexpected message: {other:?}"),
+        }
+    }
+
+    #[test]
+    fn test_subdag_message_metadata_round_trip() {
+        let metadata = create_test_metadata();
+        let json = serde_json::to_string(&SubDagMessage::MetadataOnly(metadata.clone())).unwrap();
+        assert!(json.contains(r#""type":"MetadataOnly""#));
+        match serde_json::from_str(&json).unwrap() {
+            SubDagMessage::MetadataOnly(decoded) => assert_eq!(decoded, metadata),
+            other => panic!("unexpected message: {other:?}"),
+        }
+    }
+
+    #[test]
+    fn test_serialize_summary() {
+        use crate::types::Transaction;
+        use crate::{SignedBlock, VerifiedBlock};
+        let metadata = create_test_metadata();
+        let subdag = CommittedSubDag {
+            leader: metadata.leader.clone(),
+            blocks: vec![
+                VerifiedBlock {
+                    block: SignedBlock::new(vec![
+                        Transaction::new(vec![0xab; 4]),
+                        Transaction::new(vec![0xcd; 4]),
+                    ]),
+                    digest: BlockDigest([1; DIGEST_LENGTH]),
+                },
+                VerifiedBlock {
+                    block: SignedBlock::new(vec![]),
+                    digest: BlockDigest([2; DIGEST_LENGTH]),
+                },
+            ],
+            timestamp_ms: metadata.timestamp_ms,
+            commit_ref: metadata.commit_ref,
+            reputation_scores_desc: metadata.reputation_scores_desc.clone(),
+        };
+        let json = subdag.serialize_summary().unwrap();
+        let value: serde_json::Value = serde_json::from_str(&json).unwrap();
+        assert_eq!(value["blocks"][0]["transaction_count"], 2);
+        assert_eq!(value["blocks"][1]["transaction_count"], 0);
+        assert_eq!(value["timestamp_ms"], metadata.timestamp_ms);
+        assert!(value["blocks"][0].get("block").is_none());
+        assert!(!json.contains("inner"));
+        assert!(!json.contains(&0xab.to_string()));
+        let decoded: CommitMetadata = serde_json::from_str(&json).unwrap();
+        assert_eq!(decoded, metadata);
+    }
+
+    #[test]
+    fn test_receipt_round_trip() {
+        let metadata = create_test_metadata();
+        let subdag = CommittedSubDag {
+            leader: metadata.leader.clone(),
+            blocks: vec![],
+            timestamp_ms: metadata.timestamp_ms,
+            commit_ref: metadata.commit_ref,
+            reputation_scores_desc: metadata.reputation_scores_desc.clone(),
+        };
+        let receipt = subdag.receipt();
+        assert_eq!(receipt.commit_ref, subdag.commit_ref);
+        #[cfg(feature = "sha2")]
+        assert_eq!(receipt.commit_hash, Some(subdag.commit_hash()));
+        #[cfg(not(feature = "sha2"))]
+        assert_eq!(receipt.commit_hash, None);
+        let json = serde_json::to_string(&receipt).unwrap();
+        let decoded: CommitReceipt = serde_json::from_str(&json).unwrap();
+        assert_eq!(decoded, receipt);
+    }
+
+    #[test]
+    fn test_receipt_transaction_count() {
+        use crate::types::Transaction;
+        use crate::{SignedBlock, VerifiedBlock};
+        let subdag = CommittedSubDag {
+            blocks: vec![
+                VerifiedBlock {
+                    block: SignedBlock::new(vec![
+                        Transaction::new(vec![1]),
+                        Transaction::new(vec![2]),
+                    ]),
+                    digest: BlockDigest([1; DIGEST_LENGTH]),
+                },
+                VerifiedBlock {
+                    block: SignedBlock::new(vec![Transaction::new(vec![3])]),
+                    digest: BlockDigest([2; DIGEST_LENGTH]),
+                },
+            ],
+            ..Default::default()
+        };
+        assert_eq!(subdag.receipt().transaction_count, subdag.len());
+        assert_eq!(subdag.receipt().transaction_count, 3);
+    }
+
+    #[test]
+    fn test_compact_metadata_len() {
+        let metadata = create_test_metadata();
+        let subdag = CommittedSubDag {
+            leader: metadata.leader.clone(),
+            reputation_scores_desc: metadata.reputation_scores_desc.clone(),
+            ..Default::default()
+        };
+        assert_eq!(
+            subdag.compact_metadata_len(),
+            metadata.to_compact_bytes().len()
+        );
+        assert_eq!(
+            CommittedSubDag::default().compact_metadata_len(),
+            COMPACT_METADATA_HEADER_SIZE
+        );
+    }
+}
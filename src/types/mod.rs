@@ -1,8 +1,24 @@
+mod batch;
 mod block;
+#[cfg(feature = "sha2")]
+mod chain;
+#[cfg(feature = "cbor")]
+pub mod codec;
+mod error;
+mod metadata;
 mod primitives;
+mod reputation;
+mod serde_config;
 mod subdag;
 mod transaction;
+pub use batch::*;
 pub use block::*;
+#[cfg(feature = "sha2")]
+pub use chain::*;
+pub use error::*;
+pub use metadata::*;
 pub use primitives::*;
+pub use reputation::*;
+pub use serde_config::*;
 pub use subdag::*;
 pub use transaction::*;
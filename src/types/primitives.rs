@@ -1,8 +1,11 @@
 //! Minimal primitive types for consensus-related structures.
 //! These types are defined independently to avoid external dependencies.
 
+use alloy_primitives::hex;
 use serde::{Deserialize, Serialize};
 
+use crate::types::{DigestParseError, ParseError};
+
 /// Digest length in bytes (32 bytes for SHA-256)
 pub const DIGEST_LENGTH: usize = 32;
 
@@ -20,6 +23,44 @@ pub struct BlockRef {
     pub round: u64,
 }
 
+impl BlockRef {
+    /// Format the block reference as `{leader_address}:{round}:{digest_hex}`.
+    pub fn to_id_string(&self) -> String {
+        format!(
+            "{}:{}:{}",
+            self.leader_address,
+            self.round,
+            hex::encode(self.digest)
+        )
+    }
+
+    /// Parse a block reference formatted by [`BlockRef::to_id_string`].
+    ///
+    /// The leader address must be empty or a `0x`-prefixed 20-byte hex string.
+    pub fn from_id_string(s: &str) -> Result<BlockRef, ParseError> {
+        let parts: Vec<&str> = s.split(':').collect();
+        let [leader_address, round, digest] = parts.as_slice() else {
+            return Err(ParseError::InvalidFormat);
+        };
+        let valid_address = leader_address.is_empty()
+            || leader_address
+                .strip_prefix("0x")
+                .is_some_and(|addr| addr.len() == 40 && hex::decode(addr).is_ok());
+        if !valid_address {
+            return Err(ParseError::InvalidLeaderAddress(leader_address.to_string()));
+        }
+        let round = round
+            .parse()
+            .map_err(|_| ParseError::InvalidRound(round.to_string()))?;
+        let digest = parse_digest_hex(digest).map_err(ParseError::InvalidDigest)?;
+        Ok(BlockRef {
+            leader_address: leader_address.to_string(),
+            digest,
+            round,
+        })
+    }
+}
+
 /// Commit reference - a unique identifier for a commit
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize, Default)]
 pub struct CommitRef {
@@ -29,11 +70,52 @@ pub struct CommitRef {
     pub round: usize,
 }
 
+impl CommitRef {
+    /// Build a commit reference from a hex encoded digest (with or without `0x` prefix)
+    pub fn from_hex(digest_hex: &str, round: u64) -> Result<Self, DigestParseError> {
+        let digest = parse_digest_hex(digest_hex)?;
+        let round = usize::try_from(round).map_err(|_| DigestParseError::InvalidRound(round))?;
+        Ok(Self { digest, round })
+    }
+
+    /// Build the reference of the commit led by `block_ref`, with the same digest and round.
+    ///
+    /// Fails with [`DigestParseError::InvalidRound`] if the `u64` block round does not fit in
+    /// the `usize` commit round, which can only happen on targets where `usize` is narrower than
+    /// 64 bits.
+    pub fn from_block_ref(block_ref: &BlockRef) -> Result<CommitRef, DigestParseError> {
+        let round = usize::try_from(block_ref.round)
+            .map_err(|_| DigestParseError::InvalidRound(block_ref.round))?;
+        Ok(CommitRef {
+            digest: block_ref.digest,
+            round,
+        })
+    }
+}
+
+impl TryFrom<&BlockRef> for CommitRef {
+    type Error = DigestParseError;
+
+    /// See [`CommitRef::from_block_ref`].
+    fn try_from(block_ref: &BlockRef) -> Result<Self, Self::Error> {
+        CommitRef::from_block_ref(block_ref)
+    }
+}
+
+/// Parse a hex encoded digest, with or without `0x` prefix.
+fn parse_digest_hex(digest_hex: &str) -> Result<[u8; DIGEST_LENGTH], DigestParseError> {
+    let bytes = hex::decode(digest_hex).map_err(|_| DigestParseError::InvalidHex)?;
+    bytes
+        .as_slice()
+        .try_into()
+        .map_err(|_| DigestParseError::InvalidLength(bytes.len()))
+}
+
 /// Block timestamp in milliseconds
 pub type BlockTimestampMs = u64;
 
 /// Transaction type - a simple wrapper around raw bytes
-#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub struct Transaction {
     inner: Vec<u8>,
 }
@@ -49,10 +131,23 @@ impl Transaction {
         &self.inner
     }
 
+    /// Get at most the first `max_bytes` of the transaction data.
+    ///
+    /// The preview borrows from the transaction, so no bytes are copied.
+    pub fn preview(&self, max_bytes: usize) -> &[u8] {
+        &self.inner[..self.inner.len().min(max_bytes)]
+    }
+
     /// Consume the transaction and return the inner data
     pub fn into_data(self) -> Vec<u8> {
         self.inner
     }
+
+    /// SHA-256 digest of the transaction data
+    #[cfg(feature = "sha2")]
+    pub fn digest(&self) -> [u8; DIGEST_LENGTH] {
+        crate::BlockDigest::hash_bytes(&self.inner).0
+    }
 }
 
 impl AsRef<[u8]> for Transaction {
@@ -80,19 +175,16 @@ mod tests {
             digest: digest1,
             round: 10,
             leader_address: String::new(),
-            ..Default::default()
         };
         let block_ref2 = BlockRef {
             digest: digest1,
             round: 10,
             leader_address: String::new(),
-            ..Default::default()
         };
         let block_ref3 = BlockRef {
             digest: digest1,
             round: 11,
             leader_address: String::new(),
-            ..Default::default()
         };
         assert_eq!(block_ref1, block_ref2);
         assert_ne!(block_ref1, block_ref3);
@@ -106,13 +198,109 @@ mod tests {
             digest,
             round: 100,
             leader_address: String::new(),
-            ..Default::default()
         };
         let serialized = serde_json::to_string(&block_ref).unwrap();
         let deserialized: BlockRef = serde_json::from_str(&serialized).unwrap();
         assert_eq!(block_ref, deserialized);
     }
 
+    #[test]
+    fn test_block_ref_id_string_round_trip() {
+        let mut digest = [0u8; DIGEST_LENGTH];
+        digest[0] = 0xab;
+        let block_ref = BlockRef {
+            leader_address: "0x00000000000000000000000000000000000000ff".to_string(),
+            digest,
+            round: 12,
+        };
+        let id = block_ref.to_id_string();
+        assert_eq!(
+            id,
+            format!(
+                "0x00000000000000000000000000000000000000ff:12:ab{}",
+                "00".repeat(DIGEST_LENGTH - 1)
+            )
+        );
+        assert_eq!(BlockRef::from_id_string(&id), Ok(block_ref));
+        let default = BlockRef::default();
+        assert_eq!(
+            BlockRef::from_id_string(&default.to_id_string()),
+            Ok(default)
+        );
+    }
+
+    #[test]
+    fn test_block_ref_id_string_malformed() {
+        let digest_hex = "00".repeat(DIGEST_LENGTH);
+        assert_eq!(
+            BlockRef::from_id_string("0x01:2"),
+            Err(ParseError::InvalidFormat)
+        );
+        assert_eq!(
+            BlockRef::from_id_string(&format!("validator:1:{digest_hex}")),
+            Err(ParseError::InvalidLeaderAddress("validator".to_string()))
+        );
+        assert_eq!(
+            BlockRef::from_id_string(&format!(":-1:{digest_hex}")),
+            Err(ParseError::InvalidRound("-1".to_string()))
+        );
+        assert_eq!(
+            BlockRef::from_id_string(":1:abcd"),
+            Err(ParseError::InvalidDigest(DigestParseError::InvalidLength(
+                2
+            )))
+        );
+    }
+
+    #[test]
+    fn test_commit_ref_from_block_ref() {
+        let mut digest = [0u8; DIGEST_LENGTH];
+        digest[5] = 9;
+        let block_ref = BlockRef {
+            leader_address: "0x00000000000000000000000000000000000000ff".to_string(),
+            digest,
+            round: 42,
+        };
+        assert_eq!(
+            CommitRef::from_block_ref(&block_ref),
+            Ok(CommitRef { digest, round: 42 })
+        );
+        assert_eq!(
+            CommitRef::try_from(&block_ref),
+            CommitRef::from_block_ref(&block_ref)
+        );
+    }
+
+    #[cfg(target_pointer_width = "64")]
+    #[test]
+    fn test_commit_ref_from_block_ref_high_round() {
+        let block_ref = BlockRef {
+            round: u64::MAX,
+            ..Default::default()
+        };
+        assert_eq!(
+            CommitRef::from_block_ref(&block_ref).map(|commit_ref| commit_ref.round),
+            Ok(usize::MAX)
+        );
+    }
+
+    #[cfg(not(target_pointer_width = "64"))]
+    #[test]
+    fn test_commit_ref_from_block_ref_high_round() {
+        let block_ref = BlockRef {
+            round: u64::MAX,
+            ..Default::default()
+        };
+        assert_eq!(
+            CommitRef::from_block_ref(&block_ref),
+            Err(DigestParseError::InvalidRound(u64::MAX))
+        );
+        assert_eq!(
+            CommitRef::try_from(&block_ref),
+            Err(DigestParseError::InvalidRound(u64::MAX))
+        );
+    }
+
     #[test]
     fn test_commit_ref_default() {
         let commit_ref = CommitRef::default();
@@ -150,6 +338,30 @@ mod tests {
         assert_eq!(commit_ref, deserialized);
     }
 
+    #[test]
+    fn test_commit_ref_from_hex() {
+        let mut digest = [0u8; DIGEST_LENGTH];
+        digest[0] = 0xab;
+        digest[DIGEST_LENGTH - 1] = 0x01;
+        let digest_hex = hex::encode(digest);
+        let commit_ref = CommitRef::from_hex(&digest_hex, 7).unwrap();
+        assert_eq!(commit_ref, CommitRef { digest, round: 7 });
+        let prefixed = CommitRef::from_hex(&format!("0x{digest_hex}"), 7).unwrap();
+        assert_eq!(prefixed, commit_ref);
+    }
+
+    #[test]
+    fn test_commit_ref_from_hex_malformed() {
+        assert_eq!(
+            CommitRef::from_hex("zz", 1),
+            Err(DigestParseError::InvalidHex)
+        );
+        assert_eq!(
+            CommitRef::from_hex("0xabcd", 1),
+            Err(DigestParseError::InvalidLength(2))
+        );
+    }
+
     #[test]
     fn test_transaction_new() {
         let data = vec![1, 2, 3, 4, 5];
@@ -164,6 +376,15 @@ mod tests {
         assert_eq!(tx.data(), &data);
     }
 
+    #[test]
+    fn test_transaction_preview() {
+        let tx = Transaction::new(vec![1, 2, 3, 4]);
+        assert_eq!(tx.preview(8), &[1, 2, 3, 4]);
+        assert_eq!(tx.preview(4), &[1, 2, 3, 4]);
+        assert_eq!(tx.preview(2), &[1, 2]);
+        assert!(tx.preview(0).is_empty());
+    }
+
     #[test]
     fn test_transaction_into_data() {
         let data = vec![100, 200, 255];
@@ -200,6 +421,20 @@ mod tests {
         assert_eq!(tx.data(), deserialized.data());
     }
 
+    #[test]
+    fn test_transaction_json_encoding() {
+        let tx = Transaction::new(vec![1, 2, 3]);
+        assert_eq!(serde_json::to_string(&tx).unwrap(), r#"{"inner":[1,2,3]}"#);
+    }
+
+    #[cfg(feature = "sha2")]
+    #[test]
+    fn test_transaction_digest() {
+        let tx = Transaction::new(vec![1, 2, 3]);
+        assert_eq!(tx.digest(), Transaction::new(vec![1, 2, 3]).digest());
+        assert_ne!(tx.digest(), Transaction::new(vec![1, 2]).digest());
+    }
+
     #[test]
     fn test_transaction_clone() {
         let data = vec![42, 43, 44];
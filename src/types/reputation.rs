@@ -0,0 +1,375 @@
+//! Reputation score helpers for committed subdags.
+
+use std::collections::HashMap;
+
+use crate::types::AuthorityIndex;
+use crate::CommittedSubDag;
+
+impl CommittedSubDag {
+    /// Population variance of the reputation scores, or `None` when there are no scores.
+    pub fn reputation_variance(&self) -> Option<f64> {
+        if self.reputation_scores_desc.is_empty() {
+            return None;
+        }
+        let count = self.reputation_scores_desc.len() as f64;
+        let mean = self
+            .reputation_scores_desc
+            .iter()
+            .map(|(_, score)| *score as f64)
+            .sum::<f64>()
+            / count;
+        let variance = self
+            .reputation_scores_desc
+            .iter()
+            .map(|(_, score)| (*score as f64 - mean).powi(2))
+            .sum::<f64>()
+            / count;
+        Some(variance)
+    }
+
+    /// Pick an authority with probability proportional to its reputation score.
+    ///
+    /// The choice is fully determined by `seed`, so every consumer using the same seed picks the
+    /// same authority. Returns `None` when there are no scores or they are all zero.
+    pub fn weighted_leader_choice(&self, seed: u64) -> Option<AuthorityIndex> {
+        let total: u128 = self
+            .reputation_scores_desc
+            .iter()
+            .map(|(_, score)| *score as u128)
+            .sum();
+        if total == 0 {
+            return None;
+        }
+        let mut target = splitmix64(seed) as u128 % total;
+        for (authority, score) in &self.reputation_scores_desc {
+            let score = *score as u128;
+            if target < score {
+                return Some(*authority);
+            }
+            target -= score;
+        }
+        None
+    }
+
+    /// 1-based rank of `authority` by descending reputation score, or `None` if it has no score.
+    ///
+    /// Authorities with equal scores share the same (lowest) rank, e.g. scores `[100, 90, 90, 80]`
+    /// rank as `[1, 2, 2, 4]`. The order of `reputation_scores_desc` itself is not relied upon.
+    pub fn reputation_rank(&self, authority: AuthorityIndex) -> Option<usize> {
+        let (_, score) = self
+            .reputation_scores_desc
+            .iter()
+            .find(|(candidate, _)| *candidate == authority)?;
+        let higher = self
+            .reputation_scores_desc
+            .iter()
+            .filter(|(_, other)| other > score)
+            .count();
+        Some(higher + 1)
+    }
+
+    /// Sum of all reputation scores, saturating at `u64::MAX`.
+    pub fn reputation_total(&self) -> u64 {
+        self.reputation_scores_desc
+            .iter()
+            .fold(0u64, |total, (_, score)| total.saturating_add(*score))
+    }
+
+    /// Whether the reputation scores add up to exactly `expected` (see
+    /// [`CommittedSubDag::reputation_total`]).
+    pub fn reputation_total_eq(&self, expected: u64) -> bool {
+        self.reputation_total() == expected
+    }
+
+    /// Gini coefficient of the reputation scores: 0.0 when all authorities score the same,
+    /// approaching 1.0 as reputation concentrates on a single authority.
+    ///
+    /// Returns 0.0 when there are no scores or they are all zero.
+    pub fn reputation_gini(&self) -> f64 {
+        let mut scores: Vec<f64> = self
+            .reputation_scores_desc
+            .iter()
+            .map(|(_, score)| *score as f64)
+            .collect();
+        let total: f64 = scores.iter().sum();
+        if total == 0.0 {
+            return 0.0;
+        }
+        scores.sort_by(f64::total_cmp);
+        let count = scores.len() as f64;
+        let weighted: f64 = scores
+            .iter()
+            .enumerate()
+            .map(|(i, score)| (i + 1) as f64 * score)
+            .sum();
+        2.0 * weighted / (count * total) - (count + 1.0) / count
+    }
+
+    /// Mean of the reputation scores weighted by `weights`.
+    ///
+    /// Authorities without an entry in `weights` get a weight of zero. Returns `None` when the
+    /// total weight of the scored authorities is zero.
+    pub fn weighted_avg_reputation(&self, weights: &HashMap<AuthorityIndex, u64>) -> Option<f64> {
+        let (weighted_sum, total_weight) = self.reputation_scores_desc.iter().fold(
+            (0u128, 0u128),
+            |(sum, total), (authority, score)| {
+                let weight = weights.get(authority).copied().unwrap_or(0) as u128;
+                (sum + weight * *score as u128, total + weight)
+            },
+        );
+        if total_weight == 0 {
+            return None;
+        }
+        Some(weighted_sum as f64 / total_weight as f64)
+    }
+}
+
+/// Sum of each authority's reputation scores across `subdags`, saturating at `u64::MAX`.
+pub fn aggregate_reputation(subdags: &[CommittedSubDag]) -> HashMap<AuthorityIndex, u64> {
+    let mut totals: HashMap<AuthorityIndex, u64> = HashMap::new();
+    for (authority, score) in subdags
+        .iter()
+        .flat_map(|subdag| &subdag.reputation_scores_desc)
+    {
+        let total = totals.entry(*authority).or_default();
+        *total = total.saturating_add(*score);
+    }
+    totals
+}
+
+/// The `n` authorities with the highest [`aggregate_reputation`] over `subdags`, by descending
+/// total score. Ties are broken by ascending authority index.
+pub fn top_authorities(subdags: &[CommittedSubDag], n: usize) -> Vec<(AuthorityIndex, u64)> {
+    let mut totals: Vec<_> = aggregate_reputation(subdags).into_iter().collect();
+    totals.sort_by(|a, b| b.1.cmp(&a.1).then(a.0.cmp(&b.0)));
+    totals.truncate(n);
+    totals
+}
+
+/// Authorities whose reputation score dropped by more than `threshold` from `prev` to `curr`,
+/// with the size of the drop, in `prev`'s order.
+///
+/// An authority missing from `curr` is treated as having a score of 0 there, so its whole
+/// previous score counts as the drop. Authorities only present in `curr` cannot regress.
+pub fn reputation_regressions(
+    prev: &CommittedSubDag,
+    curr: &CommittedSubDag,
+    threshold: u64,
+) -> Vec<(AuthorityIndex, u64)> {
+    let current: HashMap<AuthorityIndex, u64> =
+        curr.reputation_scores_desc.iter().copied().collect();
+    prev.reputation_scores_desc
+        .iter()
+        .filter_map(|(authority, score)| {
+            let drop = score.saturating_sub(current.get(authority).copied().unwrap_or(0));
+            (drop > threshold).then_some((*authority, drop))
+        })
+        .collect()
+}
+
+/// SplitMix64 finalizer, used to spread consecutive seeds over the whole `u64` range.
+fn splitmix64(seed: u64) -> u64 {
+    let mut z = seed.wrapping_add(0x9e37_79b9_7f4a_7c15);
+    z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+    z ^ (z >> 31)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn create_test_subdag(reputation_scores_desc: Vec<(AuthorityIndex, u64)>) -> CommittedSubDag {
+        CommittedSubDag {
+            reputation_scores_desc,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_reputation_variance_uniform() {
+        let subdag = create_test_subdag(vec![(0, 50), (1, 50), (2, 50)]);
+        assert_eq!(subdag.reputation_variance(), Some(0.0));
+    }
+
+    #[test]
+    fn test_reputation_variance_spread() {
+        let subdag = create_test_subdag(vec![(0, 90), (1, 50), (2, 10)]);
+        let variance = subdag.reputation_variance().unwrap();
+        assert!((variance - 3200.0 / 3.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_reputation_variance_empty() {
+        let subdag = create_test_subdag(vec![]);
+        assert_eq!(subdag.reputation_variance(), None);
+    }
+
+    #[test]
+    fn test_weighted_leader_choice_deterministic() {
+        let subdag = create_test_subdag(vec![(0, 50), (1, 30), (2, 20)]);
+        for seed in 0..100 {
+            assert_eq!(
+                subdag.weighted_leader_choice(seed),
+                subdag.weighted_leader_choice(seed)
+            );
+        }
+    }
+
+    #[test]
+    fn test_weighted_leader_choice_prefers_higher_weight() {
+        let subdag = create_test_subdag(vec![(0, 900), (1, 100)]);
+        let heavy = (0..1000)
+            .filter(|seed| subdag.weighted_leader_choice(*seed) == Some(0))
+            .count();
+        assert!(heavy > 800);
+        assert!(heavy < 1000);
+    }
+
+    #[test]
+    fn test_weighted_leader_choice_none() {
+        assert_eq!(create_test_subdag(vec![]).weighted_leader_choice(1), None);
+        let zero = create_test_subdag(vec![(0, 0), (1, 0)]);
+        assert_eq!(zero.weighted_leader_choice(1), None);
+    }
+
+    #[test]
+    fn test_reputation_rank() {
+        let subdag = create_test_subdag(vec![(3, 100), (1, 90), (2, 90), (0, 80)]);
+        assert_eq!(subdag.reputation_rank(3), Some(1));
+        assert_eq!(subdag.reputation_rank(0), Some(4));
+        assert_eq!(subdag.reputation_rank(7), None);
+    }
+
+    #[test]
+    fn test_reputation_rank_ties() {
+        let subdag = create_test_subdag(vec![(3, 100), (1, 90), (2, 90), (0, 80)]);
+        assert_eq!(subdag.reputation_rank(1), Some(2));
+        assert_eq!(subdag.reputation_rank(2), Some(2));
+    }
+
+    #[test]
+    fn test_reputation_total() {
+        let subdag = create_test_subdag(vec![(0, 50), (1, 30), (2, 20)]);
+        assert_eq!(subdag.reputation_total(), 100);
+        assert!(subdag.reputation_total_eq(100));
+        assert!(!subdag.reputation_total_eq(99));
+        assert!(create_test_subdag(vec![]).reputation_total_eq(0));
+    }
+
+    #[test]
+    fn test_reputation_total_saturates() {
+        let subdag = create_test_subdag(vec![(0, u64::MAX), (1, 1), (2, 5)]);
+        assert_eq!(subdag.reputation_total(), u64::MAX);
+        assert!(subdag.reputation_total_eq(u64::MAX));
+    }
+
+    #[test]
+    fn test_aggregate_reputation() {
+        let subdags = vec![
+            create_test_subdag(vec![(0, 50), (1, 30)]),
+            create_test_subdag(vec![(1, 20), (2, 10)]),
+            create_test_subdag(vec![(3, 5)]),
+        ];
+        let totals = aggregate_reputation(&subdags);
+        assert_eq!(totals, HashMap::from([(0, 50), (1, 50), (2, 10), (3, 5)]));
+        assert!(aggregate_reputation(&[]).is_empty());
+    }
+
+    #[test]
+    fn test_aggregate_reputation_saturates() {
+        let subdags = vec![
+            create_test_subdag(vec![(0, u64::MAX)]),
+            create_test_subdag(vec![(0, 1)]),
+        ];
+        assert_eq!(aggregate_reputation(&subdags)[&0], u64::MAX);
+    }
+
+    #[test]
+    fn test_reputation_gini_equal() {
+        let subdag = create_test_subdag(vec![(0, 40), (1, 40), (2, 40), (3, 40)]);
+        assert!(subdag.reputation_gini().abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_reputation_gini_skewed() {
+        let skewed = create_test_subdag(vec![(0, 100), (1, 0), (2, 0), (3, 0)]);
+        assert!((skewed.reputation_gini() - 0.75).abs() < 1e-9);
+        let mild = create_test_subdag(vec![(0, 50), (1, 40), (2, 30), (3, 20)]);
+        assert!(mild.reputation_gini() > 0.0);
+        assert!(mild.reputation_gini() < skewed.reputation_gini());
+    }
+
+    #[test]
+    fn test_reputation_gini_degenerate() {
+        assert_eq!(create_test_subdag(vec![]).reputation_gini(), 0.0);
+        assert_eq!(
+            create_test_subdag(vec![(0, 0), (1, 0)]).reputation_gini(),
+            0.0
+        );
+    }
+
+    #[test]
+    fn test_top_authorities() {
+        let subdags = vec![
+            create_test_subdag(vec![(0, 50), (1, 30), (2, 10)]),
+            create_test_subdag(vec![(1, 20), (3, 50), (2, 5)]),
+            create_test_subdag(vec![(4, 45)]),
+        ];
+        assert_eq!(
+            top_authorities(&subdags, 3),
+            vec![(0, 50), (1, 50), (3, 50)]
+        );
+        assert_eq!(top_authorities(&subdags, 1), vec![(0, 50)]);
+        assert_eq!(top_authorities(&subdags, 10).len(), 5);
+        assert!(top_authorities(&subdags, 0).is_empty());
+    }
+
+    #[test]
+    fn test_reputation_regressions() {
+        let prev = create_test_subdag(vec![(0, 100), (1, 80), (2, 60)]);
+        let curr = create_test_subdag(vec![(0, 95), (1, 40), (2, 70)]);
+        assert_eq!(reputation_regressions(&prev, &curr, 10), vec![(1, 40)]);
+        assert_eq!(
+            reputation_regressions(&prev, &curr, 0),
+            vec![(0, 5), (1, 40)]
+        );
+    }
+
+    #[test]
+    fn test_reputation_regressions_none() {
+        let prev = create_test_subdag(vec![(0, 100), (1, 80)]);
+        let curr = create_test_subdag(vec![(0, 100), (1, 75), (2, 10)]);
+        assert!(reputation_regressions(&prev, &curr, 5).is_empty());
+    }
+
+    #[test]
+    fn test_reputation_regressions_absent_authority() {
+        let prev = create_test_subdag(vec![(0, 100), (1, 30)]);
+        let curr = create_test_subdag(vec![(0, 100)]);
+        assert_eq!(reputation_regressions(&prev, &curr, 10), vec![(1, 30)]);
+        assert!(reputation_regressions(&prev, &curr, 30).is_empty());
+    }
+
+    #[test]
+    fn test_weighted_avg_reputation_uniform() {
+        let subdag = create_test_subdag(vec![(0, 90), (1, 60), (2, 30)]);
+        let weights = HashMap::from([(0, 1), (1, 1), (2, 1)]);
+        assert_eq!(subdag.weighted_avg_reputation(&weights), Some(60.0));
+    }
+
+    #[test]
+    fn test_weighted_avg_reputation_skewed() {
+        let subdag = create_test_subdag(vec![(0, 90), (1, 60), (2, 30)]);
+        let weights = HashMap::from([(0, 3), (2, 1)]);
+        assert_eq!(subdag.weighted_avg_reputation(&weights), Some(75.0));
+    }
+
+    #[test]
+    fn test_weighted_avg_reputation_zero_weight() {
+        let subdag = create_test_subdag(vec![(0, 90), (1, 60)]);
+        assert_eq!(subdag.weighted_avg_reputation(&HashMap::new()), None);
+        let weights = HashMap::from([(0, 0), (5, 10)]);
+        assert_eq!(subdag.weighted_avg_reputation(&weights), None);
+    }
+}
@@ -0,0 +1,234 @@
+//! Runtime-configurable serialization of committed subdags.
+
+use alloy_primitives::hex;
+use base64::Engine;
+use serde::ser::{SerializeSeq, SerializeStruct};
+use serde::{Serialize, Serializer};
+
+use crate::types::{BlockRef, CommitRef, DIGEST_LENGTH};
+use crate::{CommittedSubDag, VerifiedBlock};
+
+/// Encoding used for digest fields (block digests, leader and commit digests).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum DigestEncoding {
+    /// Same as the derived representation: an array of byte values
+    #[default]
+    Array,
+    /// `0x`-prefixed lowercase hex string
+    Hex,
+    /// Standard base64 string with padding, as used by `BlockDigest`'s `Display`
+    Base64,
+}
+
+/// Serialization options chosen at runtime.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct SerdeConfig {
+    pub digest_encoding: DigestEncoding,
+}
+
+/// A `CommittedSubDag` serialized according to a `SerdeConfig`.
+///
+/// The output has the same shape as the derived `Serialize` of `CommittedSubDag`; only the
+/// encoding of digest fields differs. It is meant for output only: non-`Array` encodings can't
+/// be deserialized back into a `CommittedSubDag`.
+#[derive(Debug, Clone, Copy)]
+pub struct ConfiguredSubDag<'a> {
+    subdag: &'a CommittedSubDag,
+    config: SerdeConfig,
+}
+
+impl<'a> ConfiguredSubDag<'a> {
+    pub fn new(subdag: &'a CommittedSubDag, config: SerdeConfig) -> Self {
+        Self { subdag, config }
+    }
+}
+
+impl Serialize for ConfiguredSubDag<'_> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let encoding = self.config.digest_encoding;
+        let mut state = serializer.serialize_struct("CommittedSubDag", 5)?;
+        state.serialize_field(
+            "leader",
+            &ConfiguredBlockRef {
+                block_ref: &self.subdag.leader,
+                encoding,
+            },
+        )?;
+        state.serialize_field(
+            "blocks",
+            &ConfiguredBlocks {
+                blocks: &self.subdag.blocks,
+                encoding,
+            },
+        )?;
+        state.serialize_field("timestamp_ms", &self.subdag.timestamp_ms)?;
+        state.serialize_field(
+            "commit_ref",
+            &ConfiguredCommitRef {
+                commit_ref: &self.subdag.commit_ref,
+                encoding,
+            },
+        )?;
+        state.serialize_field(
+            "reputation_scores_desc",
+            &self.subdag.reputation_scores_desc,
+        )?;
+        state.end()
+    }
+}
+
+struct ConfiguredDigest<'a> {
+    digest: &'a [u8; DIGEST_LENGTH],
+    encoding: DigestEncoding,
+}
+
+impl Serialize for ConfiguredDigest<'_> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        match self.encoding {
+            DigestEncoding::Array => self.digest.serialize(serializer),
+            DigestEncoding::Hex => {
+                serializer.serialize_str(&format!("0x{}", hex::encode(self.digest)))
+            }
+            DigestEncoding::Base64 => serializer
+                .serialize_str(&base64::engine::general_purpose::STANDARD.encode(self.digest)),
+        }
+    }
+}
+
+struct ConfiguredBlockRef<'a> {
+    block_ref: &'a BlockRef,
+    encoding: DigestEncoding,
+}
+
+impl Serialize for ConfiguredBlockRef<'_> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut state = serializer.serialize_struct("BlockRef", 3)?;
+        state.serialize_field("leader_address", &self.block_ref.leader_address)?;
+        state.serialize_field(
+            "digest",
+            &ConfiguredDigest {
+                digest: &self.block_ref.digest,
+                encoding: self.encoding,
+            },
+        )?;
+        state.serialize_field("round", &self.block_ref.round)?;
+        state.end()
+    }
+}
+
+struct ConfiguredCommitRef<'a> {
+    commit_ref: &'a CommitRef,
+    encoding: DigestEncoding,
+}
+
+impl Serialize for ConfiguredCommitRef<'_> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut state = serializer.serialize_struct("CommitRef", 2)?;
+        state.serialize_field(
+            "digest",
+            &ConfiguredDigest {
+                digest: &self.commit_ref.digest,
+                encoding: self.encoding,
+            },
+        )?;
+        state.serialize_field("round", &self.commit_ref.round)?;
+        state.end()
+    }
+}
+
+struct ConfiguredBlocks<'a> {
+    blocks: &'a [VerifiedBlock],
+    encoding: DigestEncoding,
+}
+
+impl Serialize for ConfiguredBlocks<'_> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut seq = serializer.serialize_seq(Some(self.blocks.len()))?;
+        for block in self.blocks {
+            seq.serialize_element(&ConfiguredBlock {
+                block,
+                encoding: self.encoding,
+            })?;
+        }
+        seq.end()
+    }
+}
+
+struct ConfiguredBlock<'a> {
+    block: &'a VerifiedBlock,
+    encoding: DigestEncoding,
+}
+
+impl Serialize for ConfiguredBlock<'_> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut state = serializer.serialize_struct("VerifiedBlock", 2)?;
+        state.serialize_field("block", &self.block.block)?;
+        state.serialize_field(
+            "digest",
+            &ConfiguredDigest {
+                digest: &self.block.digest.0,
+                encoding: self.encoding,
+            },
+        )?;
+        state.end()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::Transaction;
+    use crate::{BlockDigest, SignedBlock};
+
+    fn create_test_committed_subdag() -> CommittedSubDag {
+        let mut digest = [0u8; DIGEST_LENGTH];
+        digest[0] = 0xab;
+        CommittedSubDag {
+            leader: BlockRef {
+                leader_address: String::new(),
+                digest,
+                round: 1,
+            },
+            blocks: vec![VerifiedBlock {
+                block: SignedBlock::new(vec![Transaction::new(vec![1, 2, 3])]),
+                digest: BlockDigest(digest),
+            }],
+            timestamp_ms: 1000,
+            commit_ref: CommitRef { digest, round: 1 },
+            reputation_scores_desc: vec![(0, 100)],
+        }
+    }
+
+    fn serialize(subdag: &CommittedSubDag, digest_encoding: DigestEncoding) -> serde_json::Value {
+        let config = SerdeConfig { digest_encoding };
+        serde_json::to_value(ConfiguredSubDag::new(subdag, config)).unwrap()
+    }
+
+    #[test]
+    fn test_configured_subdag_array_matches_derived() {
+        let subdag = create_test_committed_subdag();
+        let value = serialize(&subdag, DigestEncoding::Array);
+        assert_eq!(value, serde_json::to_value(&subdag).unwrap());
+        assert!(value["leader"]["digest"].is_array());
+    }
+
+    #[test]
+    fn test_configured_subdag_hex() {
+        let subdag = create_test_committed_subdag();
+        let value = serialize(&subdag, DigestEncoding::Hex);
+        let expected = format!("0xab{}", "00".repeat(DIGEST_LENGTH - 1));
+        assert_eq!(value["leader"]["digest"], expected.as_str());
+        assert_eq!(value["commit_ref"]["digest"], expected.as_str());
+        assert_eq!(value["blocks"][0]["digest"], expected.as_str());
+    }
+
+    #[test]
+    fn test_configured_subdag_base64() {
+        let subdag = create_test_committed_subdag();
+        let value = serialize(&subdag, DigestEncoding::Base64);
+        let expected = format!("{:?}", subdag.blocks[0].digest);
+        assert_eq!(value["leader"]["digest"], expected.as_str());
+        assert_eq!(value["commit_ref"]["digest"], expected.as_str());
+        assert_eq!(value["blocks"][0]["digest"], expected.as_str());
+    }
+}
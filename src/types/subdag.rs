@@ -1,15 +1,72 @@
+use std::collections::{BTreeMap, HashMap, HashSet};
 use std::fmt::Debug;
 
+use alloy_primitives::hex;
 use serde::{Deserialize, Serialize};
 
-use crate::types::{AuthorityIndex, BlockRef, BlockTimestampMs, CommitRef};
+use crate::types::{
+    AuthorityIndex, BlockRef, BlockTimestampMs, CommitRef, CountMismatch, EmptyBlockError,
+    NoBlocksError, OversizedTransaction, Transaction,
+};
 use crate::{BlockDigest, SignedBlock};
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+/// A `SignedBlock` together with its digest.
+///
+/// Note: blocks do not carry their own timestamp, so it is not possible to check that block
+/// timestamps within a subdag don't exceed the commit's `timestamp_ms`. Consumers needing that
+/// guarantee must obtain block timestamps out of band.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub struct VerifiedBlock {
     pub block: SignedBlock,
     pub digest: BlockDigest,
 }
+impl VerifiedBlock {
+    /// Check that the block carries at least one transaction.
+    pub fn require_non_empty(&self) -> Result<(), EmptyBlockError> {
+        if self.block.transactions().is_empty() {
+            return Err(EmptyBlockError {
+                digest: self.digest,
+            });
+        }
+        Ok(())
+    }
+}
+#[cfg(feature = "sha2")]
+impl VerifiedBlock {
+    /// Start building a `VerifiedBlock` whose digest is computed on `finalize`.
+    pub fn builder() -> VerifiedBlockBuilder {
+        VerifiedBlockBuilder::default()
+    }
+}
+
+/// Builder for `VerifiedBlock`, see [`VerifiedBlock::builder`].
+#[cfg(feature = "sha2")]
+#[derive(Debug, Clone, Default)]
+pub struct VerifiedBlockBuilder {
+    transactions: Vec<Transaction>,
+    signature: Vec<u8>,
+}
+
+#[cfg(feature = "sha2")]
+impl VerifiedBlockBuilder {
+    /// Set the transactions of the block
+    pub fn transactions(mut self, transactions: Vec<Transaction>) -> Self {
+        self.transactions = transactions;
+        self
+    }
+
+    /// Set the signature of the block
+    pub fn signature(mut self, signature: Vec<u8>) -> Self {
+        self.signature = signature;
+        self
+    }
+
+    /// Build the block, computing its digest with `SignedBlock::compute_digest`.
+    pub fn finalize(self) -> VerifiedBlock {
+        SignedBlock::with_signature(self.transactions, self.signature).into_verified()
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize, Default)]
 pub struct CommittedSubDag {
     pub leader: BlockRef,
@@ -18,6 +75,19 @@ pub struct CommittedSubDag {
     pub commit_ref: CommitRef,
     pub reputation_scores_desc: Vec<(AuthorityIndex, u64)>,
 }
+/// Fixed per-block overhead counted by [`CommittedSubDag::storage_footprint`]: the block digest.
+/// The signature is variable-sized and counted separately.
+pub const BLOCK_STORAGE_OVERHEAD: usize = crate::types::DIGEST_LENGTH;
+
+/// A transaction tagged with the commit round and block it was committed in, as produced by
+/// [`CommittedSubDag::into_tagged_transactions`].
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TaggedTransaction {
+    pub commit_round: u64,
+    pub block_digest: BlockDigest,
+    pub data: Vec<u8>,
+}
+
 impl CommittedSubDag {
     pub fn flatten_transactions(&self) -> Vec<Vec<u8>> {
         self.blocks
@@ -31,12 +101,705 @@ impl CommittedSubDag {
             })
             .collect()
     }
+    /// Number of transactions across all blocks; see `block_count` for the number of blocks.
     pub fn len(&self) -> usize {
         self.blocks
             .iter()
             .map(|block| block.block.transactions().len())
             .sum()
     }
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+    /// Iterate over all transactions in flatten order (block order, then transaction order).
+    pub(crate) fn transactions(&self) -> impl Iterator<Item = &Transaction> {
+        self.blocks
+            .iter()
+            .flat_map(|block| block.block.transactions().iter())
+    }
+    /// Find the first transaction matching `pred`, returning its flatten-order index.
+    pub fn find_transaction<F: Fn(&Transaction) -> bool>(
+        &self,
+        pred: F,
+    ) -> Option<(usize, &Transaction)> {
+        self.transactions().enumerate().find(|(_, tx)| pred(tx))
+    }
+    /// Compare two subdags by content, treating the reputation scores as an unordered collection.
+    ///
+    /// `CommittedSubDag` does not implement `PartialEq`; this is the comparison to use for
+    /// deduplication, since a re-sorted `reputation_scores_desc` still describes the same commit.
+    pub fn content_eq(&self, other: &Self) -> bool {
+        let mut scores = self.reputation_scores_desc.clone();
+        let mut other_scores = other.reputation_scores_desc.clone();
+        scores.sort_unstable();
+        other_scores.sort_unstable();
+        self.leader == other.leader
+            && self.commit_ref == other.commit_ref
+            && self.timestamp_ms == other.timestamp_ms
+            && self.blocks == other.blocks
+            && scores == other_scores
+    }
+    /// Digests of blocks only present in `self` and only present in `other`, in block order.
+    pub fn block_digest_diff(&self, other: &Self) -> (Vec<BlockDigest>, Vec<BlockDigest>) {
+        let own: HashSet<BlockDigest> = self.blocks.iter().map(|block| block.digest).collect();
+        let theirs: HashSet<BlockDigest> = other.blocks.iter().map(|block| block.digest).collect();
+        let only_in_self = self
+            .blocks
+            .iter()
+            .map(|block| block.digest)
+            .filter(|digest| !theirs.contains(digest))
+            .collect();
+        let only_in_other = other
+            .blocks
+            .iter()
+            .map(|block| block.digest)
+            .filter(|digest| !own.contains(digest))
+            .collect();
+        (only_in_self, only_in_other)
+    }
+    /// Whether a byte-equal transaction is part of this subdag.
+    ///
+    /// This scans every block, so it is O(n) in the number of transactions. For repeated lookups
+    /// against a large subdag, build a hash set of the transactions once instead.
+    pub fn contains_transaction(&self, tx: &Transaction) -> bool {
+        self.transactions().any(|candidate| candidate == tx)
+    }
+    /// Transactions in flatten order, keeping only the first occurrence of byte-identical ones.
+    pub fn unique_transactions(&self) -> Vec<&Transaction> {
+        let mut seen = HashSet::new();
+        self.transactions().filter(|tx| seen.insert(*tx)).collect()
+    }
+    /// Number of blocks attributed to the authority with address `addr`.
+    ///
+    /// Blocks don't carry their author, so every block of the subdag is attributed to the commit
+    /// leader (`leader.leader_address`): this returns the block count for the leader and 0 for
+    /// any other address.
+    pub fn blocks_led_by(&self, addr: &str) -> usize {
+        if self.leader.leader_address == addr {
+            self.blocks.len()
+        } else {
+            0
+        }
+    }
+    /// Number of transactions for each exact transaction byte length.
+    pub fn length_counts(&self) -> BTreeMap<usize, usize> {
+        let mut counts = BTreeMap::new();
+        for tx in self.transactions() {
+            *counts.entry(tx.data().len()).or_insert(0) += 1;
+        }
+        counts
+    }
+    /// SHA-256 over the canonical JSON serialization of the whole subdag (see
+    /// [`CommittedSubDag::canonical_json`]).
+    #[cfg(feature = "sha2")]
+    pub fn commit_hash(&self) -> [u8; crate::types::DIGEST_LENGTH] {
+        let bytes = to_canonical_json_vec(self).expect("CommittedSubDag serialization cannot fail");
+        BlockDigest::hash_bytes(&bytes).0
+    }
+    /// First transaction in flatten order.
+    pub fn first_transaction(&self) -> Option<&Transaction> {
+        self.transactions().next()
+    }
+    /// Last transaction in flatten order.
+    pub fn last_transaction(&self) -> Option<&Transaction> {
+        self.blocks
+            .iter()
+            .rev()
+            .find_map(|block| block.block.transactions().last())
+    }
+    /// Size-based cost of the subdag: total transaction bytes times `per_byte`.
+    ///
+    /// The computation saturates at `u64::MAX` instead of overflowing.
+    pub fn byte_cost(&self, per_byte: u64) -> u64 {
+        self.transactions()
+            .map(|tx| tx.data().len() as u64)
+            .fold(0u64, u64::saturating_add)
+            .saturating_mul(per_byte)
+    }
+    /// Minimal subdag for `round`: one empty unsigned block that is also the leader, a commit
+    /// ref at the same round, a zero timestamp and no reputation scores.
+    ///
+    /// The block digest is a deterministic placeholder derived from the round (not a hash of the
+    /// block), so that leader and commit digests are non-zero and unique per round.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `round` does not fit in the `usize` commit round (see
+    /// [`CommitRef::from_block_ref`]), which can only happen on targets where `usize` is
+    /// narrower than 64 bits.
+    pub fn minimal(round: u64) -> Self {
+        let mut digest = [0u8; crate::types::DIGEST_LENGTH];
+        digest[..8].copy_from_slice(&round.to_le_bytes());
+        digest[crate::types::DIGEST_LENGTH - 1] = 1;
+        let leader = BlockRef {
+            leader_address: String::new(),
+            digest,
+            round,
+        };
+        let commit_ref = CommitRef::try_from(&leader).expect("round does not fit in usize");
+        Self {
+            leader,
+            blocks: vec![VerifiedBlock {
+                block: SignedBlock::default(),
+                digest: BlockDigest(digest),
+            }],
+            timestamp_ms: 0,
+            commit_ref,
+            reputation_scores_desc: vec![],
+        }
+    }
+    /// Number of distinct transactions, identified by their SHA-256 digest.
+    #[cfg(feature = "sha2")]
+    pub fn unique_transaction_count(&self) -> usize {
+        self.transactions()
+            .map(Transaction::digest)
+            .collect::<HashSet<_>>()
+            .len()
+    }
+    /// Copy of the subdag where every transaction matching `should_redact` is replaced by an
+    /// empty transaction.
+    ///
+    /// Block structure, signatures and digests are kept as they are, so redacted blocks no longer
+    /// verify against their digest.
+    pub fn redact_transactions<F: Fn(&Transaction) -> bool>(
+        &self,
+        should_redact: F,
+    ) -> CommittedSubDag {
+        let blocks = self
+            .blocks
+            .iter()
+            .map(|block| {
+                let transactions = block
+                    .block
+                    .transactions()
+                    .iter()
+                    .map(|tx| {
+                        if should_redact(tx) {
+                            Transaction::new(Vec::new())
+                        } else {
+                            tx.clone()
+                        }
+                    })
+                    .collect();
+                VerifiedBlock {
+                    block: SignedBlock::with_signature(
+                        transactions,
+                        block.block.signature().to_vec(),
+                    ),
+                    digest: block.digest,
+                }
+            })
+            .collect();
+        CommittedSubDag {
+            leader: self.leader.clone(),
+            blocks,
+            timestamp_ms: self.timestamp_ms,
+            commit_ref: self.commit_ref,
+            reputation_scores_desc: self.reputation_scores_desc.clone(),
+        }
+    }
+    /// Whether two or more blocks share the same digest.
+    pub fn has_duplicate_blocks(&self) -> bool {
+        let mut seen = HashSet::new();
+        !self.blocks.iter().all(|block| seen.insert(block.digest))
+    }
+    /// Digests appearing on more than one block, each listed once in order of first repetition.
+    pub fn duplicate_block_digests(&self) -> Vec<BlockDigest> {
+        let mut seen = HashSet::new();
+        let mut duplicates = Vec::new();
+        for block in &self.blocks {
+            if !seen.insert(block.digest) && !duplicates.contains(&block.digest) {
+                duplicates.push(block.digest);
+            }
+        }
+        duplicates
+    }
+    /// Serialize the subdag as a single newline-terminated JSON line (NDJSON).
+    pub fn to_ndjson_line(&self) -> Result<String, serde_json::Error> {
+        let mut line = serde_json::to_string(self)?;
+        line.push('\n');
+        Ok(line)
+    }
+    /// Fraction of transaction bytes carried by signed blocks.
+    ///
+    /// Returns 0.0 when the subdag holds no transaction bytes at all.
+    pub fn signed_byte_ratio(&self) -> f64 {
+        let (signed, total) = self.blocks.iter().fold((0usize, 0usize), |acc, block| {
+            let bytes: usize = block
+                .block
+                .transactions()
+                .iter()
+                .map(|tx| tx.data().len())
+                .sum();
+            if block.block.is_signed() {
+                (acc.0 + bytes, acc.1 + bytes)
+            } else {
+                (acc.0, acc.1 + bytes)
+            }
+        });
+        if total == 0 {
+            return 0.0;
+        }
+        signed as f64 / total as f64
+    }
+    /// Digest of the first block containing a byte-equal transaction.
+    pub fn block_of_transaction(&self, tx: &Transaction) -> Option<BlockDigest> {
+        self.blocks
+            .iter()
+            .find(|block| block.block.transactions().contains(tx))
+            .map(|block| block.digest)
+    }
+    /// The leader's digest as a `BlockDigest`, for comparison with block digests.
+    pub fn leader_digest(&self) -> BlockDigest {
+        BlockDigest(self.leader.digest)
+    }
+    /// Whether the block referenced by `leader` is one of the subdag's blocks.
+    pub fn has_leader_block(&self) -> bool {
+        let leader = self.leader_digest();
+        self.blocks.iter().any(|block| block.digest == leader)
+    }
+    /// Split the blocks into (signed, unsigned), preserving block order within each group.
+    pub fn partition_signed(&self) -> (Vec<&VerifiedBlock>, Vec<&VerifiedBlock>) {
+        self.blocks
+            .iter()
+            .partition(|block| block.block.is_signed())
+    }
+    /// Transactions whose SHA-256 digest is not in `blacklist`, in flatten order.
+    #[cfg(feature = "sha2")]
+    pub fn exclude_hashes(&self, blacklist: &HashSet<[u8; 32]>) -> Vec<&Transaction> {
+        self.transactions()
+            .filter(|tx| !blacklist.contains(&tx.digest()))
+            .collect()
+    }
+    /// Number of blocks in the subdag.
+    pub fn block_count(&self) -> usize {
+        self.blocks.len()
+    }
+    /// Whether the commit digest is set, i.e. not the all-zero genesis sentinel.
+    pub fn has_valid_commit_digest(&self) -> bool {
+        self.commit_ref.digest != [0u8; crate::types::DIGEST_LENGTH]
+    }
+    /// JSON serialization with object keys sorted recursively, usable as a stable hashing
+    /// preimage across nodes and serde versions.
+    pub fn canonical_json(&self) -> Result<String, serde_json::Error> {
+        let value = serde_json::to_value(self)?;
+        serde_json::to_string(&canonicalize_json(value))
+    }
+    /// Byte length of the largest transaction, or `None` if there are no transactions.
+    pub fn max_transaction_size(&self) -> Option<usize> {
+        self.transactions().map(|tx| tx.data().len()).max()
+    }
+    /// Copy of the subdag with `timestamp_ms` replaced.
+    pub fn with_timestamp(&self, timestamp_ms: BlockTimestampMs) -> CommittedSubDag {
+        CommittedSubDag {
+            timestamp_ms,
+            ..self.clone()
+        }
+    }
+    /// Whether both subdags refer to the same commit (same commit digest and round), regardless
+    /// of how their contents are represented.
+    pub fn same_commit(&self, other: &Self) -> bool {
+        self.commit_ref == other.commit_ref
+    }
+    /// SHA-256 root over all transactions, fed to the hasher one transaction at a time.
+    ///
+    /// Transactions are hashed in flatten order, each framed as its byte length (`u64`,
+    /// little-endian) followed by its bytes. No intermediate buffer is built, so memory use does
+    /// not grow with the size of the subdag.
+    #[cfg(feature = "sha2")]
+    pub fn transactions_root_streaming(&self) -> [u8; 32] {
+        use sha2::{Digest, Sha256};
+        let mut hasher = Sha256::new();
+        for tx in self.transactions() {
+            hasher.update((tx.data().len() as u64).to_le_bytes());
+            hasher.update(tx.data());
+        }
+        hasher.finalize().into()
+    }
+    /// Transactions whose byte length lies in `[min, max]` (both bounds inclusive), in flatten
+    /// order.
+    pub fn transactions_in_size_range(&self, min: usize, max: usize) -> Vec<&Transaction> {
+        self.transactions()
+            .filter(|tx| (min..=max).contains(&tx.data().len()))
+            .collect()
+    }
+    /// Number of transactions carried by signed blocks.
+    pub fn signed_transaction_count(&self) -> usize {
+        self.blocks
+            .iter()
+            .filter(|block| block.block.is_signed())
+            .map(|block| block.block.transactions().len())
+            .sum()
+    }
+    /// Assemble a subdag from verified blocks and commit metadata, with no reputation scores.
+    pub fn from_blocks(
+        blocks: Vec<VerifiedBlock>,
+        leader: BlockRef,
+        commit_ref: CommitRef,
+        timestamp_ms: BlockTimestampMs,
+    ) -> Self {
+        Self {
+            leader,
+            blocks,
+            timestamp_ms,
+            commit_ref,
+            reputation_scores_desc: vec![],
+        }
+    }
+    /// Shannon entropy, in bits, of the transaction size distribution (see `length_counts`).
+    ///
+    /// 0.0 means every transaction has the same size; empty subdags also return 0.0.
+    pub fn size_entropy(&self) -> f64 {
+        let total = self.len();
+        if total == 0 {
+            return 0.0;
+        }
+        self.length_counts()
+            .values()
+            .map(|count| {
+                let p = *count as f64 / total as f64;
+                -p * p.log2()
+            })
+            .sum()
+    }
+    /// Total number of transaction bytes across all blocks.
+    pub fn total_bytes(&self) -> usize {
+        self.transactions().map(|tx| tx.data().len()).sum()
+    }
+    /// Whether the flattened transactions carry exactly `expected_total` bytes.
+    pub fn verify_flattened_bytes(&self, expected_total: usize) -> bool {
+        self.total_bytes() == expected_total
+    }
+    /// Fraction of the subdag's transactions contained in the leader block.
+    ///
+    /// Blocks don't carry their author, so the leader block is found by matching block digests
+    /// against `leader.digest`. Returns 0.0 when the leader block is not part of the subdag or
+    /// the subdag has no transactions.
+    pub fn leader_transaction_share(&self) -> f64 {
+        let total = self.len();
+        if total == 0 {
+            return 0.0;
+        }
+        let leader = self.leader_digest();
+        let in_leader_block = self
+            .blocks
+            .iter()
+            .find(|block| block.digest == leader)
+            .map_or(0, |block| block.block.transactions().len());
+        in_leader_block as f64 / total as f64
+    }
+    /// Compact identifier for logs: `R{commit_round}-{first 6 hex chars of the commit digest}`.
+    pub fn short_id(&self) -> String {
+        format!(
+            "R{}-{}",
+            self.commit_ref.round,
+            hex::encode(&self.commit_ref.digest[..3])
+        )
+    }
+    /// Iterate over the blocks together with whether each one is signed.
+    pub fn blocks_with_signing_status(&self) -> impl Iterator<Item = (&VerifiedBlock, bool)> {
+        self.blocks
+            .iter()
+            .map(|block| (block, block.block.is_signed()))
+    }
+    /// Bloom filter over the SHA-256 digests of the transactions, `bits` rounded up to a whole
+    /// number of bytes. Query it with [`bloom_contains`].
+    #[cfg(feature = "sha2")]
+    pub fn transaction_bloom(&self, bits: usize) -> Vec<u8> {
+        let mut filter = vec![0u8; bits.div_ceil(8)];
+        let nbits = filter.len() * 8;
+        if nbits == 0 {
+            return filter;
+        }
+        for tx in self.transactions() {
+            for bit in bloom_bit_indices(&tx.digest(), nbits) {
+                filter[bit / 8] |= 1 << (bit % 8);
+            }
+        }
+        filter
+    }
+    /// Transactions grouped by their leading four bytes (the EVM function selector), each group
+    /// in flatten order. Transactions shorter than four bytes have no selector and are skipped.
+    pub fn group_by_selector(&self) -> HashMap<[u8; 4], Vec<&Transaction>> {
+        let mut groups: HashMap<[u8; 4], Vec<&Transaction>> = HashMap::new();
+        for tx in self.transactions() {
+            if let Some(selector) = tx.data().first_chunk::<4>() {
+                groups.entry(*selector).or_default().push(tx);
+            }
+        }
+        groups
+    }
+    /// Zero-based index of the commit counted from `genesis_round`, or `None` if the commit round
+    /// is below genesis.
+    pub fn commit_index(&self, genesis_round: u64) -> Option<u64> {
+        (self.commit_ref.round as u64).checked_sub(genesis_round)
+    }
+    /// Append `tx` to the transactions of the last block.
+    ///
+    /// The block's `digest` (and signature) are not updated, so the caller must recompute the
+    /// digest, e.g. with `SignedBlock::compute_digest`, before relying on it.
+    pub fn push_transaction_to_last_block(&mut self, tx: Transaction) -> Result<(), NoBlocksError> {
+        let block = self.blocks.last_mut().ok_or(NoBlocksError)?;
+        block.block.push_transaction(tx);
+        Ok(())
+    }
+    /// Fraction of blocks that carry no transactions.
+    ///
+    /// Returns 0.0 when the subdag has no blocks at all.
+    pub fn empty_block_ratio(&self) -> f64 {
+        if self.blocks.is_empty() {
+            return 0.0;
+        }
+        let empty = self
+            .blocks
+            .iter()
+            .filter(|block| block.block.transactions().is_empty())
+            .count();
+        empty as f64 / self.blocks.len() as f64
+    }
+    /// Number of transactions sharing each leading `prefix_len`-byte prefix. Transactions shorter
+    /// than `prefix_len` are keyed by their full bytes.
+    pub fn group_by_prefix(&self, prefix_len: usize) -> HashMap<Vec<u8>, usize> {
+        let mut counts = HashMap::new();
+        for tx in self.transactions() {
+            *counts.entry(tx.preview(prefix_len).to_vec()).or_default() += 1;
+        }
+        counts
+    }
+    /// Check that the subdag carries exactly `expected` transactions.
+    pub fn expect_transaction_count(&self, expected: usize) -> Result<(), CountMismatch> {
+        let actual = self.len();
+        if actual != expected {
+            return Err(CountMismatch { expected, actual });
+        }
+        Ok(())
+    }
+    /// All transaction bytes concatenated into one blob, plus the `(offset, length)` of each
+    /// transaction within the blob, in flatten order.
+    pub fn to_blob_with_index(&self) -> (Vec<u8>, Vec<(usize, usize)>) {
+        let mut blob = Vec::with_capacity(self.total_bytes());
+        let mut index = Vec::with_capacity(self.len());
+        for tx in self.transactions() {
+            index.push((blob.len(), tx.data().len()));
+            blob.extend_from_slice(tx.data());
+        }
+        (blob, index)
+    }
+    /// Median transaction byte length, or `None` if there are no transactions.
+    ///
+    /// With an even number of transactions, the two middle lengths are averaged, rounding down.
+    pub fn median_transaction_size(&self) -> Option<usize> {
+        let mut sizes: Vec<usize> = self.transactions().map(|tx| tx.data().len()).collect();
+        if sizes.is_empty() {
+            return None;
+        }
+        sizes.sort_unstable();
+        let mid = sizes.len() / 2;
+        if sizes.len() % 2 == 1 {
+            Some(sizes[mid])
+        } else {
+            Some(sizes[mid - 1] + (sizes[mid] - sizes[mid - 1]) / 2)
+        }
+    }
+    /// Whether the leader's address is in `allowed`.
+    ///
+    /// The comparison is exact and therefore case-sensitive, so hex addresses should be
+    /// normalized (e.g. lowercased) on both sides first.
+    pub fn leader_in_set(&self, allowed: &HashSet<String>) -> bool {
+        allowed.contains(&self.leader.leader_address)
+    }
+    /// Number of transactions in each block, in block order.
+    pub fn block_transaction_counts(&self) -> Vec<usize> {
+        self.blocks
+            .iter()
+            .map(|block| block.block.transactions().len())
+            .collect()
+    }
+    /// Check that no transaction is longer than `max_bytes`, reporting the first one that is.
+    pub fn all_transactions_within(&self, max_bytes: usize) -> Result<(), OversizedTransaction> {
+        match self
+            .transactions()
+            .map(|tx| tx.data().len())
+            .enumerate()
+            .find(|(_, size)| *size > max_bytes)
+        {
+            Some((index, size)) => Err(OversizedTransaction {
+                index,
+                size,
+                max_bytes,
+            }),
+            None => Ok(()),
+        }
+    }
+    /// Consume the subdag into one self-describing record per transaction, in flatten order.
+    pub fn into_tagged_transactions(self) -> Vec<TaggedTransaction> {
+        let commit_round = self.commit_ref.round as u64;
+        self.blocks
+            .into_iter()
+            .flat_map(|block| {
+                let block_digest = block.digest;
+                block
+                    .block
+                    .into_transactions()
+                    .into_iter()
+                    .map(move |tx| TaggedTransaction {
+                        commit_round,
+                        block_digest,
+                        data: tx.into_data(),
+                    })
+            })
+            .collect()
+    }
+    /// Sort key `(commit_round, block_index, tx_index)` of every transaction, in flatten order.
+    ///
+    /// `block_index` is the position of the block within the subdag and `tx_index` the position
+    /// of the transaction within its block, both zero-based. Comparing keys lexicographically
+    /// gives a total order over transactions across commits, as long as each commit round is
+    /// only seen once.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the subdag has more than `u32::MAX` blocks or a block has more than `u32::MAX`
+    /// transactions, since the indices could then no longer be ordered uniquely.
+    pub fn transaction_order_keys(&self) -> Vec<(u64, u32, u32)> {
+        let commit_round = self.commit_ref.round as u64;
+        self.blocks
+            .iter()
+            .enumerate()
+            .flat_map(|(block_index, block)| {
+                let block_index = u32::try_from(block_index).expect("block index fits in u32");
+                (0..block.block.transactions().len()).map(move |tx_index| {
+                    let tx_index = u32::try_from(tx_index).expect("transaction index fits in u32");
+                    (commit_round, block_index, tx_index)
+                })
+            })
+            .collect()
+    }
+    /// Consume the subdag and return its signed blocks, dropping their digests.
+    pub fn into_signed_blocks(self) -> Vec<SignedBlock> {
+        self.blocks.into_iter().map(|block| block.block).collect()
+    }
+    /// Estimated number of bytes needed to store the subdag.
+    ///
+    /// The estimate is the sum of:
+    /// - the transaction bytes (see [`CommittedSubDag::total_bytes`]),
+    /// - per block, [`BLOCK_STORAGE_OVERHEAD`] bytes plus its signature bytes,
+    /// - the commit metadata, sized as its compact encoding
+    ///   (see [`crate::CommitMetadata::to_compact_bytes`]).
+    ///
+    /// Transactions add no overhead beyond their bytes: encoding framing such as length
+    /// prefixes is not included.
+    pub fn storage_footprint(&self) -> usize {
+        let block_overhead: usize = self
+            .blocks
+            .iter()
+            .map(|block| BLOCK_STORAGE_OVERHEAD + block.block.signature().len())
+            .sum();
+        self.total_bytes() + block_overhead + self.compact_metadata_len()
+    }
+    /// Await every block of `stream`, then assemble them into a subdag with the given commit
+    /// metadata (see [`CommittedSubDag::from_blocks`]). Blocks keep their stream order.
+    #[cfg(feature = "tokio")]
+    pub async fn collect_from_stream<S: futures::Stream<Item = VerifiedBlock>>(
+        stream: S,
+        leader: BlockRef,
+        commit_ref: CommitRef,
+        timestamp_ms: BlockTimestampMs,
+    ) -> CommittedSubDag {
+        use futures::StreamExt;
+        let blocks = stream.collect().await;
+        Self::from_blocks(blocks, leader, commit_ref, timestamp_ms)
+    }
+    /// Digests of the blocks present in both `self` and `other`, in `self`'s block order and
+    /// without repetition.
+    pub fn shared_blocks(&self, other: &Self) -> Vec<BlockDigest> {
+        let others: HashSet<BlockDigest> = other.blocks.iter().map(|block| block.digest).collect();
+        let mut seen = HashSet::new();
+        self.blocks
+            .iter()
+            .map(|block| block.digest)
+            .filter(|digest| others.contains(digest) && seen.insert(*digest))
+            .collect()
+    }
+    /// Approximate number of distinct senders, assuming each transaction embeds its sender at
+    /// byte offsets `sender_range` (start inclusive, end exclusive).
+    ///
+    /// Transactions whose data does not cover the whole range are skipped. An empty range yields
+    /// 1 as soon as any transaction is long enough, since every sender then compares equal.
+    pub fn estimate_unique_senders(&self, sender_range: std::ops::Range<usize>) -> usize {
+        self.transactions()
+            .filter_map(|tx| tx.data().get(sender_range.clone()))
+            .collect::<HashSet<_>>()
+            .len()
+    }
+    /// Whether the leader block's round equals the commit round.
+    ///
+    /// The commit round is a `usize` and is widened to `u64` for the comparison, which is
+    /// lossless on all supported targets.
+    pub fn leader_round_matches_commit(&self) -> bool {
+        self.leader.round == self.commit_ref.round as u64
+    }
+}
+
+/// Number of bits set per transaction in a bloom filter built by
+/// [`CommittedSubDag::transaction_bloom`].
+#[cfg(feature = "sha2")]
+const BLOOM_HASH_COUNT: usize = 3;
+
+/// Whether `tx` may be present in a filter built by [`CommittedSubDag::transaction_bloom`].
+///
+/// `false` means the transaction is definitely absent; `true` may be a false positive. An empty
+/// filter cannot rule anything out and always returns `true`.
+#[cfg(feature = "sha2")]
+pub fn bloom_contains(filter: &[u8], tx: &Transaction) -> bool {
+    let nbits = filter.len() * 8;
+    if nbits == 0 {
+        return true;
+    }
+    bloom_bit_indices(&tx.digest(), nbits).all(|bit| filter[bit / 8] & (1 << (bit % 8)) != 0)
+}
+
+/// Bit positions of a digest in a bloom filter of `nbits` bits, one per 8-byte chunk of the
+/// digest.
+#[cfg(feature = "sha2")]
+fn bloom_bit_indices(
+    digest: &[u8; crate::types::DIGEST_LENGTH],
+    nbits: usize,
+) -> impl Iterator<Item = usize> + '_ {
+    digest
+        .chunks_exact(8)
+        .take(BLOOM_HASH_COUNT)
+        .map(move |chunk| {
+            let hash = u64::from_le_bytes(chunk.try_into().expect("chunk is 8 bytes"));
+            (hash % nbits as u64) as usize
+        })
+}
+
+/// JSON serialization of `value` with object keys sorted recursively, the preimage of the
+/// crate's SHA-256 digests.
+#[cfg(feature = "sha2")]
+pub(crate) fn to_canonical_json_vec<T: Serialize>(value: &T) -> Result<Vec<u8>, serde_json::Error> {
+    serde_json::to_vec(&canonicalize_json(serde_json::to_value(value)?))
+}
+
+/// Rebuild a JSON value with the keys of every object in sorted order.
+fn canonicalize_json(value: serde_json::Value) -> serde_json::Value {
+    match value {
+        serde_json::Value::Object(map) => {
+            let mut entries: Vec<_> = map.into_iter().collect();
+            entries.sort_by(|a, b| a.0.cmp(&b.0));
+            serde_json::Value::Object(
+                entries
+                    .into_iter()
+                    .map(|(key, value)| (key, canonicalize_json(value)))
+                    .collect(),
+            )
+        }
+        serde_json::Value::Array(items) => {
+            serde_json::Value::Array(items.into_iter().map(canonicalize_json).collect())
+        }
+        other => other,
+    }
 }
 // Note: If you need to convert from external consensus types, implement From trait
 // for your specific consensus library types. This keeps the crate independent.
@@ -54,7 +817,6 @@ mod tests {
             digest,
             round,
             leader_address: String::new(),
-            ..Default::default()
         }
     }
 
@@ -78,6 +840,35 @@ mod tests {
         }
     }
 
+    fn create_test_verified_block_with_digest(
+        seed: u8,
+        transactions: Vec<Transaction>,
+    ) -> VerifiedBlock {
+        let mut digest = [0u8; 32];
+        digest[0] = seed;
+        VerifiedBlock {
+            block: create_test_signed_block(transactions),
+            digest: BlockDigest(digest),
+        }
+    }
+
+    fn create_test_signed_verified_block(transactions: Vec<Transaction>) -> VerifiedBlock {
+        VerifiedBlock {
+            block: SignedBlock::with_signature(transactions, vec![0xaa; 64]),
+            digest: BlockDigest([2u8; 32]),
+        }
+    }
+
+    fn create_test_subdag_with_digests(seeds: &[u8]) -> CommittedSubDag {
+        CommittedSubDag {
+            blocks: seeds
+                .iter()
+                .map(|seed| create_test_verified_block_with_digest(*seed, vec![]))
+                .collect(),
+            ..Default::default()
+        }
+    }
+
     #[test]
     fn test_committed_subdag_default() {
         let subdag = CommittedSubDag::default();
@@ -210,6 +1001,22 @@ mod tests {
         );
     }
 
+    #[cfg(feature = "sha2")]
+    #[test]
+    fn test_verified_block_builder() {
+        use sha2::{Digest, Sha256};
+        let transactions = vec![Transaction::new(vec![1, 2, 3])];
+        let verified_block = VerifiedBlock::builder()
+            .transactions(transactions.clone())
+            .signature(vec![4, 5, 6])
+            .finalize();
+        assert_eq!(verified_block.block.transactions(), &transactions);
+        assert_eq!(verified_block.block.signature(), &[4, 5, 6]);
+        let expected: [u8; 32] =
+            Sha256::digest(to_canonical_json_vec(&verified_block.block).unwrap()).into();
+        assert_eq!(verified_block.digest, BlockDigest(expected));
+    }
+
     #[test]
     fn test_verified_block_clone() {
         let verified_block = create_test_verified_block(vec![Transaction::new(vec![1, 2, 3])]);
@@ -220,4 +1027,1344 @@ mod tests {
             cloned.block.transactions()
         );
     }
+
+    #[test]
+    fn test_find_transaction() {
+        let block1 = create_test_verified_block(vec![
+            Transaction::new(vec![1, 2, 3]),
+            Transaction::new(vec![4, 5, 6]),
+        ]);
+        let block2 = create_test_verified_block(vec![Transaction::new(vec![7, 8, 9])]);
+        let subdag = CommittedSubDag {
+            blocks: vec![block1, block2],
+            ..Default::default()
+        };
+        let (index, tx) = subdag
+            .find_transaction(|tx| tx.data().starts_with(&[7]))
+            .unwrap();
+        assert_eq!(index, 2);
+        assert_eq!(tx.data(), &[7, 8, 9]);
+    }
+
+    #[test]
+    fn test_find_transaction_not_found() {
+        let subdag = CommittedSubDag {
+            blocks: vec![create_test_verified_block(vec![Transaction::new(vec![
+                1, 2, 3,
+            ])])],
+            ..Default::default()
+        };
+        assert!(subdag.find_transaction(|tx| tx.data().is_empty()).is_none());
+    }
+
+    #[test]
+    fn test_content_eq_reordered_reputation() {
+        let subdag = CommittedSubDag {
+            leader: create_test_block_ref(1),
+            blocks: vec![create_test_verified_block(vec![Transaction::new(vec![
+                1, 2, 3,
+            ])])],
+            timestamp_ms: 1000,
+            commit_ref: create_test_commit_ref(1),
+            reputation_scores_desc: vec![(0, 100), (1, 90), (2, 90)],
+        };
+        let mut reordered = subdag.clone();
+        reordered.reputation_scores_desc = vec![(2, 90), (0, 100), (1, 90)];
+        assert!(subdag.content_eq(&reordered));
+    }
+
+    #[test]
+    fn test_content_eq_content_differs() {
+        let subdag = CommittedSubDag {
+            leader: create_test_block_ref(1),
+            blocks: vec![create_test_verified_block(vec![Transaction::new(vec![
+                1, 2, 3,
+            ])])],
+            timestamp_ms: 1000,
+            commit_ref: create_test_commit_ref(1),
+            reputation_scores_desc: vec![(0, 100)],
+        };
+        let mut other_blocks = subdag.clone();
+        other_blocks.blocks = vec![create_test_verified_block(vec![Transaction::new(vec![4])])];
+        assert!(!subdag.content_eq(&other_blocks));
+        let mut other_scores = subdag.clone();
+        other_scores.reputation_scores_desc = vec![(0, 99)];
+        assert!(!subdag.content_eq(&other_scores));
+    }
+
+    #[test]
+    fn test_block_digest_diff_identical() {
+        let subdag = create_test_subdag_with_digests(&[1, 2, 3]);
+        let (only_in_self, only_in_other) = subdag.block_digest_diff(&subdag.clone());
+        assert!(only_in_self.is_empty());
+        assert!(only_in_other.is_empty());
+    }
+
+    #[test]
+    fn test_block_digest_diff_disjoint() {
+        let subdag1 = create_test_subdag_with_digests(&[1, 2]);
+        let subdag2 = create_test_subdag_with_digests(&[3]);
+        let (only_in_self, only_in_other) = subdag1.block_digest_diff(&subdag2);
+        assert_eq!(
+            only_in_self,
+            vec![subdag1.blocks[0].digest, subdag1.blocks[1].digest]
+        );
+        assert_eq!(only_in_other, vec![subdag2.blocks[0].digest]);
+    }
+
+    #[test]
+    fn test_block_digest_diff_partial_overlap() {
+        let subdag1 = create_test_subdag_with_digests(&[1, 2, 3]);
+        let subdag2 = create_test_subdag_with_digests(&[2, 3, 4]);
+        let (only_in_self, only_in_other) = subdag1.block_digest_diff(&subdag2);
+        assert_eq!(only_in_self, vec![subdag1.blocks[0].digest]);
+        assert_eq!(only_in_other, vec![subdag2.blocks[2].digest]);
+    }
+
+    #[test]
+    fn test_contains_transaction() {
+        let subdag = CommittedSubDag {
+            blocks: vec![
+                create_test_verified_block(vec![Transaction::new(vec![1, 2, 3])]),
+                create_test_verified_block(vec![Transaction::new(vec![4, 5, 6])]),
+            ],
+            ..Default::default()
+        };
+        assert!(subdag.contains_transaction(&Transaction::new(vec![4, 5, 6])));
+        assert!(!subdag.contains_transaction(&Transaction::new(vec![4, 5])));
+    }
+
+    #[test]
+    fn test_contains_transaction_empty_subdag() {
+        let subdag = CommittedSubDag::default();
+        assert!(!subdag.contains_transaction(&Transaction::new(vec![])));
+    }
+
+    #[test]
+    fn test_unique_transactions_no_duplicates() {
+        let subdag = CommittedSubDag {
+            blocks: vec![
+                create_test_verified_block(vec![
+                    Transaction::new(vec![1]),
+                    Transaction::new(vec![2]),
+                ]),
+                create_test_verified_block(vec![Transaction::new(vec![3])]),
+            ],
+            ..Default::default()
+        };
+        assert_eq!(subdag.unique_transactions().len(), 3);
+    }
+
+    #[test]
+    fn test_unique_transactions_cross_block_duplicates() {
+        let subdag = CommittedSubDag {
+            blocks: vec![
+                create_test_verified_block(vec![
+                    Transaction::new(vec![1]),
+                    Transaction::new(vec![2]),
+                ]),
+                create_test_verified_block(vec![
+                    Transaction::new(vec![2]),
+                    Transaction::new(vec![3]),
+                    Transaction::new(vec![1]),
+                ]),
+            ],
+            ..Default::default()
+        };
+        let unique: Vec<_> = subdag
+            .unique_transactions()
+            .into_iter()
+            .map(|tx| tx.data().to_vec())
+            .collect();
+        assert_eq!(unique, vec![vec![1], vec![2], vec![3]]);
+    }
+
+    #[test]
+    fn test_blocks_led_by() {
+        let mut subdag = create_test_subdag_with_digests(&[1, 2, 3]);
+        subdag.leader.leader_address = "0x0000000000000000000000000000000000000001".to_string();
+        assert_eq!(
+            subdag.blocks_led_by("0x0000000000000000000000000000000000000001"),
+            3
+        );
+        assert_eq!(
+            subdag.blocks_led_by("0x0000000000000000000000000000000000000002"),
+            0
+        );
+    }
+
+    #[test]
+    fn test_length_counts() {
+        let subdag = CommittedSubDag {
+            blocks: vec![
+                create_test_verified_block(vec![
+                    Transaction::new(vec![1]),
+                    Transaction::new(vec![1, 2, 3]),
+                ]),
+                create_test_verified_block(vec![
+                    Transaction::new(vec![4, 5, 6]),
+                    Transaction::new(vec![]),
+                    Transaction::new(vec![7]),
+                ]),
+            ],
+            ..Default::default()
+        };
+        let counts = subdag.length_counts();
+        assert_eq!(
+            counts.into_iter().collect::<Vec<_>>(),
+            vec![(0, 1), (1, 2), (3, 2)]
+        );
+    }
+
+    #[cfg(feature = "sha2")]
+    #[test]
+    fn test_commit_hash() {
+        let subdag = CommittedSubDag {
+            leader: create_test_block_ref(1),
+            blocks: vec![create_test_verified_block(vec![Transaction::new(vec![
+                1, 2, 3,
+            ])])],
+            timestamp_ms: 1000,
+            commit_ref: create_test_commit_ref(1),
+            reputation_scores_desc: vec![(0, 100)],
+        };
+        assert_eq!(subdag.commit_hash(), subdag.clone().commit_hash());
+        let mut other = subdag.clone();
+        other.timestamp_ms += 1;
+        assert_ne!(subdag.commit_hash(), other.commit_hash());
+        assert_eq!(
+            subdag.commit_hash(),
+            BlockDigest::hash_bytes(subdag.canonical_json().unwrap().as_bytes()).0
+        );
+    }
+
+    #[test]
+    fn test_first_last_transaction_empty() {
+        let subdag = CommittedSubDag {
+            blocks: vec![create_test_verified_block(vec![])],
+            ..Default::default()
+        };
+        assert!(subdag.first_transaction().is_none());
+        assert!(subdag.last_transaction().is_none());
+    }
+
+    #[test]
+    fn test_first_last_transaction_single() {
+        let subdag = CommittedSubDag {
+            blocks: vec![create_test_verified_block(vec![Transaction::new(vec![1])])],
+            ..Default::default()
+        };
+        assert_eq!(subdag.first_transaction().unwrap().data(), &[1]);
+        assert_eq!(subdag.last_transaction().unwrap().data(), &[1]);
+    }
+
+    #[test]
+    fn test_first_last_transaction_multiple() {
+        let subdag = CommittedSubDag {
+            blocks: vec![
+                create_test_verified_block(vec![]),
+                create_test_verified_block(vec![
+                    Transaction::new(vec![1]),
+                    Transaction::new(vec![2]),
+                ]),
+                create_test_verified_block(vec![Transaction::new(vec![3])]),
+                create_test_verified_block(vec![]),
+            ],
+            ..Default::default()
+        };
+        assert_eq!(subdag.first_transaction().unwrap().data(), &[1]);
+        assert_eq!(subdag.last_transaction().unwrap().data(), &[3]);
+    }
+
+    #[test]
+    fn test_byte_cost() {
+        let subdag = CommittedSubDag {
+            blocks: vec![
+                create_test_verified_block(vec![Transaction::new(vec![1, 2, 3])]),
+                create_test_verified_block(vec![Transaction::new(vec![4, 5])]),
+            ],
+            ..Default::default()
+        };
+        assert_eq!(subdag.byte_cost(10), 50);
+        assert_eq!(subdag.byte_cost(0), 0);
+    }
+
+    #[test]
+    fn test_byte_cost_saturates() {
+        let subdag = CommittedSubDag {
+            blocks: vec![create_test_verified_block(vec![Transaction::new(vec![
+                0u8;
+                16
+            ])])],
+            ..Default::default()
+        };
+        assert_eq!(subdag.byte_cost(u64::MAX / 2), u64::MAX);
+    }
+
+    #[test]
+    fn test_minimal() {
+        let subdag = CommittedSubDag::minimal(5);
+        assert_eq!(subdag.blocks.len(), 1);
+        assert!(subdag.is_empty());
+        assert_eq!(subdag.leader.round, 5);
+        assert_eq!(subdag.commit_ref.round, 5);
+        assert_eq!(subdag.leader.digest, subdag.blocks[0].digest.0);
+        assert_eq!(subdag.commit_ref.digest, subdag.leader.digest);
+        assert_ne!(subdag.commit_ref.digest, [0u8; 32]);
+        assert_eq!(subdag.timestamp_ms, 0);
+        assert!(subdag.reputation_scores_desc.is_empty());
+        assert_ne!(
+            CommittedSubDag::minimal(6).commit_ref.digest,
+            subdag.commit_ref.digest
+        );
+    }
+
+    #[test]
+    fn test_minimal_is_structurally_valid() {
+        // The crate has no `validate`; check the invariants it would cover with the existing
+        // structural checks.
+        let subdag = CommittedSubDag::minimal(5);
+        assert!(subdag.has_leader_block());
+        assert!(subdag.leader_round_matches_commit());
+        assert!(subdag.has_valid_commit_digest());
+        assert!(!subdag.has_duplicate_blocks());
+        assert_eq!(subdag.expect_transaction_count(0), Ok(()));
+        assert_eq!(subdag.all_transactions_within(0), Ok(()));
+        // The single block is empty by design, so a no-empty-block policy rejects it.
+        assert!(subdag.blocks[0].require_non_empty().is_err());
+    }
+
+    #[cfg(target_pointer_width = "64")]
+    #[test]
+    fn test_minimal_high_round() {
+        let subdag = CommittedSubDag::minimal(u64::MAX);
+        assert_eq!(subdag.commit_ref.round, usize::MAX);
+        assert!(subdag.leader_round_matches_commit());
+    }
+
+    #[cfg(feature = "sha2")]
+    #[test]
+    fn test_unique_transaction_count() {
+        let unique = CommittedSubDag {
+            blocks: vec![create_test_verified_block(vec![
+                Transaction::new(vec![1]),
+                Transaction::new(vec![2]),
+            ])],
+            ..Default::default()
+        };
+        assert_eq!(unique.unique_transaction_count(), 2);
+        let duplicated = CommittedSubDag {
+            blocks: vec![
+                create_test_verified_block(vec![
+                    Transaction::new(vec![1]),
+                    Transaction::new(vec![2]),
+                ]),
+                create_test_verified_block(vec![Transaction::new(vec![1])]),
+            ],
+            ..Default::default()
+        };
+        assert_eq!(duplicated.len(), 3);
+        assert_eq!(duplicated.unique_transaction_count(), 2);
+    }
+
+    #[test]
+    fn test_redact_transactions() {
+        let subdag = CommittedSubDag {
+            blocks: vec![
+                create_test_verified_block(vec![
+                    Transaction::new(vec![0xde, 0xad]),
+                    Transaction::new(vec![1, 2, 3]),
+                ]),
+                create_test_verified_block(vec![Transaction::new(vec![0xde, 0xff])]),
+            ],
+            ..Default::default()
+        };
+        let redacted = subdag.redact_transactions(|tx| tx.data().starts_with(&[0xde]));
+        assert_eq!(
+            redacted.flatten_transactions(),
+            vec![vec![], vec![1, 2, 3], vec![]]
+        );
+        assert_eq!(redacted.blocks.len(), subdag.blocks.len());
+        assert_eq!(redacted.blocks[0].digest, subdag.blocks[0].digest);
+        assert_eq!(subdag.flatten_transactions()[0], vec![0xde, 0xad]);
+    }
+
+    #[test]
+    fn test_duplicate_blocks_unique() {
+        let subdag = create_test_subdag_with_digests(&[1, 2, 3]);
+        assert!(!subdag.has_duplicate_blocks());
+        assert!(subdag.duplicate_block_digests().is_empty());
+    }
+
+    #[test]
+    fn test_duplicate_blocks() {
+        let subdag = create_test_subdag_with_digests(&[1, 2, 1, 3, 2, 1]);
+        assert!(subdag.has_duplicate_blocks());
+        assert_eq!(
+            subdag.duplicate_block_digests(),
+            vec![subdag.blocks[0].digest, subdag.blocks[1].digest]
+        );
+    }
+
+    #[test]
+    fn test_to_ndjson_line() {
+        let subdag1 = CommittedSubDag {
+            blocks: vec![create_test_verified_block(vec![Transaction::new(vec![
+                1, 2, 3,
+            ])])],
+            commit_ref: create_test_commit_ref(1),
+            ..Default::default()
+        };
+        let subdag2 = CommittedSubDag {
+            commit_ref: create_test_commit_ref(2),
+            ..Default::default()
+        };
+        let line = subdag1.to_ndjson_line().unwrap();
+        assert!(line.ends_with('\n'));
+        assert_eq!(line.matches('\n').count(), 1);
+        let archive = line + &subdag2.to_ndjson_line().unwrap();
+        let decoded: Vec<CommittedSubDag> = serde_json::Deserializer::from_str(&archive)
+            .into_iter()
+            .collect::<Result<_, _>>()
+            .unwrap();
+        assert_eq!(decoded.len(), 2);
+        assert_eq!(decoded[0].commit_ref, subdag1.commit_ref);
+        assert_eq!(
+            decoded[0].flatten_transactions(),
+            subdag1.flatten_transactions()
+        );
+        assert_eq!(decoded[1].commit_ref, subdag2.commit_ref);
+    }
+
+    #[test]
+    fn test_signed_byte_ratio_all_signed() {
+        let subdag = CommittedSubDag {
+            blocks: vec![create_test_signed_verified_block(vec![Transaction::new(
+                vec![1, 2, 3],
+            )])],
+            ..Default::default()
+        };
+        assert_eq!(subdag.signed_byte_ratio(), 1.0);
+    }
+
+    #[test]
+    fn test_signed_byte_ratio_none_signed() {
+        let subdag = CommittedSubDag {
+            blocks: vec![create_test_verified_block(vec![Transaction::new(vec![
+                1, 2, 3,
+            ])])],
+            ..Default::default()
+        };
+        assert_eq!(subdag.signed_byte_ratio(), 0.0);
+        assert_eq!(CommittedSubDag::default().signed_byte_ratio(), 0.0);
+    }
+
+    #[test]
+    fn test_signed_byte_ratio_mixed() {
+        let subdag = CommittedSubDag {
+            blocks: vec![
+                create_test_signed_verified_block(vec![Transaction::new(vec![1, 2, 3])]),
+                create_test_verified_block(vec![Transaction::new(vec![4])]),
+            ],
+            ..Default::default()
+        };
+        assert_eq!(subdag.signed_byte_ratio(), 0.75);
+    }
+
+    #[test]
+    fn test_block_of_transaction() {
+        let subdag = CommittedSubDag {
+            blocks: vec![
+                create_test_verified_block_with_digest(1, vec![Transaction::new(vec![1])]),
+                create_test_verified_block_with_digest(2, vec![Transaction::new(vec![2])]),
+            ],
+            ..Default::default()
+        };
+        assert_eq!(
+            subdag.block_of_transaction(&Transaction::new(vec![2])),
+            Some(subdag.blocks[1].digest)
+        );
+        assert_eq!(
+            subdag.block_of_transaction(&Transaction::new(vec![3])),
+            None
+        );
+    }
+
+    #[test]
+    fn test_leader_digest() {
+        let subdag = CommittedSubDag {
+            leader: create_test_block_ref(7),
+            ..Default::default()
+        };
+        assert_eq!(subdag.leader_digest().0, subdag.leader.digest);
+        assert_eq!(subdag.leader_digest().as_ref(), &subdag.leader.digest);
+    }
+
+    #[test]
+    fn test_has_leader_block() {
+        let mut subdag = create_test_subdag_with_digests(&[1, 2, 3]);
+        subdag.leader = create_test_block_ref(2);
+        assert!(subdag.has_leader_block());
+        subdag.leader = create_test_block_ref(4);
+        assert!(!subdag.has_leader_block());
+    }
+
+    #[test]
+    fn test_partition_signed() {
+        let subdag = CommittedSubDag {
+            blocks: vec![
+                create_test_signed_verified_block(vec![Transaction::new(vec![1])]),
+                create_test_verified_block(vec![Transaction::new(vec![2])]),
+                create_test_signed_verified_block(vec![Transaction::new(vec![3])]),
+            ],
+            ..Default::default()
+        };
+        let (signed, unsigned) = subdag.partition_signed();
+        assert_eq!(signed.len(), 2);
+        assert_eq!(unsigned.len(), 1);
+        assert!(signed.iter().all(|block| block.block.is_signed()));
+        assert_eq!(signed[1].block.transactions()[0].data(), &[3]);
+        assert_eq!(unsigned[0].block.transactions()[0].data(), &[2]);
+    }
+
+    #[cfg(feature = "sha2")]
+    #[test]
+    fn test_exclude_hashes() {
+        let transactions = vec![
+            Transaction::new(vec![1]),
+            Transaction::new(vec![2]),
+            Transaction::new(vec![3]),
+        ];
+        let subdag = CommittedSubDag {
+            blocks: vec![create_test_verified_block(transactions.clone())],
+            ..Default::default()
+        };
+        assert_eq!(subdag.exclude_hashes(&HashSet::new()).len(), 3);
+        let partial: HashSet<_> = [transactions[1].digest()].into_iter().collect();
+        let kept: Vec<_> = subdag
+            .exclude_hashes(&partial)
+            .into_iter()
+            .map(|tx| tx.data()[0])
+            .collect();
+        assert_eq!(kept, vec![1, 3]);
+        let full: HashSet<_> = transactions.iter().map(Transaction::digest).collect();
+        assert!(subdag.exclude_hashes(&full).is_empty());
+    }
+
+    #[test]
+    fn test_block_count() {
+        let subdag = CommittedSubDag {
+            blocks: vec![
+                create_test_verified_block(vec![
+                    Transaction::new(vec![1]),
+                    Transaction::new(vec![2]),
+                ]),
+                create_test_verified_block(vec![]),
+                create_test_verified_block(vec![Transaction::new(vec![3])]),
+            ],
+            ..Default::default()
+        };
+        assert_eq!(subdag.block_count(), 3);
+        assert_eq!(subdag.len(), 3);
+        assert_eq!(CommittedSubDag::default().block_count(), 0);
+    }
+
+    #[test]
+    fn test_has_valid_commit_digest() {
+        let zero = CommittedSubDag::default();
+        assert!(!zero.has_valid_commit_digest());
+        let committed = CommittedSubDag {
+            commit_ref: create_test_commit_ref(1),
+            ..Default::default()
+        };
+        assert!(committed.has_valid_commit_digest());
+    }
+
+    #[test]
+    fn test_canonical_json() {
+        let subdag = CommittedSubDag {
+            leader: create_test_block_ref(1),
+            blocks: vec![create_test_verified_block(vec![Transaction::new(vec![1])])],
+            timestamp_ms: 1000,
+            commit_ref: create_test_commit_ref(1),
+            reputation_scores_desc: vec![(0, 100)],
+        };
+        let json = subdag.canonical_json().unwrap();
+        assert_eq!(json, subdag.canonical_json().unwrap());
+        let positions: Vec<_> = [
+            "\"blocks\"",
+            "\"commit_ref\"",
+            "\"leader\"",
+            "\"reputation_scores_desc\"",
+            "\"timestamp_ms\"",
+        ]
+        .iter()
+        .map(|key| json.find(key).unwrap())
+        .collect();
+        assert!(positions.windows(2).all(|pair| pair[0] < pair[1]));
+        // Nested objects are sorted as well
+        assert!(json.contains(r#""leader":{"digest":"#));
+        let decoded: CommittedSubDag = serde_json::from_str(&json).unwrap();
+        assert_eq!(decoded.commit_ref, subdag.commit_ref);
+    }
+
+    #[test]
+    fn test_max_transaction_size() {
+        assert_eq!(CommittedSubDag::default().max_transaction_size(), None);
+        let subdag = CommittedSubDag {
+            blocks: vec![
+                create_test_verified_block(vec![Transaction::new(vec![1, 2])]),
+                create_test_verified_block(vec![
+                    Transaction::new(vec![0; 10]),
+                    Transaction::new(vec![3]),
+                ]),
+            ],
+            ..Default::default()
+        };
+        assert_eq!(subdag.max_transaction_size(), Some(10));
+    }
+
+    #[test]
+    fn test_with_timestamp() {
+        let subdag = CommittedSubDag {
+            leader: create_test_block_ref(1),
+            blocks: vec![create_test_verified_block(vec![Transaction::new(vec![1])])],
+            timestamp_ms: 1000,
+            commit_ref: create_test_commit_ref(1),
+            reputation_scores_desc: vec![(0, 100)],
+        };
+        let redated = subdag.with_timestamp(5000);
+        assert_eq!(redated.timestamp_ms, 5000);
+        assert_eq!(subdag.timestamp_ms, 1000);
+        assert_eq!(redated.leader, subdag.leader);
+        assert_eq!(redated.blocks, subdag.blocks);
+        assert_eq!(redated.commit_ref, subdag.commit_ref);
+        assert_eq!(
+            redated.reputation_scores_desc,
+            subdag.reputation_scores_desc
+        );
+    }
+
+    #[test]
+    fn test_same_commit() {
+        let subdag = CommittedSubDag {
+            commit_ref: create_test_commit_ref(1),
+            ..Default::default()
+        };
+        let same = CommittedSubDag {
+            commit_ref: create_test_commit_ref(1),
+            blocks: vec![create_test_verified_block(vec![Transaction::new(vec![1])])],
+            timestamp_ms: 42,
+            ..Default::default()
+        };
+        assert!(subdag.same_commit(&same));
+        let mut different = subdag.clone();
+        different.commit_ref.round = 2;
+        assert!(!subdag.same_commit(&different));
+        let mut different_digest = subdag.clone();
+        different_digest.commit_ref.digest[1] = 1;
+        assert!(!subdag.same_commit(&different_digest));
+    }
+
+    #[cfg(feature = "sha2")]
+    #[test]
+    fn test_transactions_root_streaming() {
+        let subdag = CommittedSubDag {
+            blocks: vec![
+                create_test_verified_block(vec![
+                    Transaction::new(vec![1, 2, 3]),
+                    Transaction::new(vec![]),
+                ]),
+                create_test_verified_block(vec![Transaction::new(vec![4, 5])]),
+            ],
+            ..Default::default()
+        };
+        let mut buffer = Vec::new();
+        for tx in subdag.flatten_transactions() {
+            buffer.extend_from_slice(&(tx.len() as u64).to_le_bytes());
+            buffer.extend_from_slice(&tx);
+        }
+        assert_eq!(
+            subdag.transactions_root_streaming(),
+            BlockDigest::hash_bytes(&buffer).0
+        );
+        // Framing keeps transaction boundaries significant
+        let merged = CommittedSubDag {
+            blocks: vec![create_test_verified_block(vec![Transaction::new(vec![
+                1, 2, 3, 4, 5,
+            ])])],
+            ..Default::default()
+        };
+        assert_ne!(
+            subdag.transactions_root_streaming(),
+            merged.transactions_root_streaming()
+        );
+    }
+
+    #[test]
+    fn test_transactions_in_size_range() {
+        let subdag = CommittedSubDag {
+            blocks: vec![create_test_verified_block(vec![
+                Transaction::new(vec![]),
+                Transaction::new(vec![1]),
+                Transaction::new(vec![1, 2]),
+                Transaction::new(vec![1, 2, 3]),
+            ])],
+            ..Default::default()
+        };
+        let sizes = |min, max| -> Vec<usize> {
+            subdag
+                .transactions_in_size_range(min, max)
+                .iter()
+                .map(|tx| tx.data().len())
+                .collect()
+        };
+        assert_eq!(sizes(1, 2), vec![1, 2]);
+        assert_eq!(sizes(0, 0), vec![0]);
+        assert_eq!(sizes(0, usize::MAX), vec![0, 1, 2, 3]);
+        assert!(sizes(4, 10).is_empty());
+        assert!(sizes(3, 1).is_empty());
+    }
+
+    #[test]
+    fn test_signed_transaction_count() {
+        let subdag = CommittedSubDag {
+            blocks: vec![
+                create_test_signed_verified_block(vec![
+                    Transaction::new(vec![1]),
+                    Transaction::new(vec![2]),
+                ]),
+                create_test_verified_block(vec![Transaction::new(vec![3])]),
+                create_test_signed_verified_block(vec![Transaction::new(vec![4])]),
+            ],
+            ..Default::default()
+        };
+        assert_eq!(subdag.signed_transaction_count(), 3);
+        assert_eq!(subdag.len(), 4);
+        let unsigned = CommittedSubDag {
+            blocks: vec![create_test_verified_block(vec![Transaction::new(vec![1])])],
+            ..Default::default()
+        };
+        assert_eq!(unsigned.signed_transaction_count(), 0);
+    }
+
+    #[test]
+    fn test_from_blocks() {
+        let blocks = vec![
+            create_test_verified_block_with_digest(1, vec![Transaction::new(vec![1])]),
+            create_test_verified_block_with_digest(2, vec![Transaction::new(vec![2])]),
+        ];
+        let subdag = CommittedSubDag::from_blocks(
+            blocks.clone(),
+            create_test_block_ref(3),
+            create_test_commit_ref(3),
+            1234,
+        );
+        assert_eq!(subdag.blocks, blocks);
+        assert_eq!(subdag.leader, create_test_block_ref(3));
+        assert_eq!(subdag.commit_ref, create_test_commit_ref(3));
+        assert_eq!(subdag.timestamp_ms, 1234);
+        assert!(subdag.reputation_scores_desc.is_empty());
+    }
+
+    #[test]
+    fn test_size_entropy() {
+        assert_eq!(CommittedSubDag::default().size_entropy(), 0.0);
+        let uniform = CommittedSubDag {
+            blocks: vec![create_test_verified_block(vec![
+                Transaction::new(vec![1, 2]),
+                Transaction::new(vec![3, 4]),
+                Transaction::new(vec![5, 6]),
+            ])],
+            ..Default::default()
+        };
+        assert_eq!(uniform.size_entropy(), 0.0);
+        let varied = CommittedSubDag {
+            blocks: vec![create_test_verified_block(vec![
+                Transaction::new(vec![1]),
+                Transaction::new(vec![1, 2]),
+                Transaction::new(vec![1, 2, 3]),
+                Transaction::new(vec![1, 2, 3, 4]),
+            ])],
+            ..Default::default()
+        };
+        assert!((varied.size_entropy() - 2.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_verify_flattened_bytes() {
+        let subdag = CommittedSubDag {
+            blocks: vec![
+                create_test_verified_block(vec![Transaction::new(vec![1, 2, 3])]),
+                create_test_verified_block(vec![Transaction::new(vec![4, 5])]),
+            ],
+            ..Default::default()
+        };
+        assert_eq!(subdag.total_bytes(), 5);
+        assert!(subdag.verify_flattened_bytes(5));
+        assert!(!subdag.verify_flattened_bytes(4));
+    }
+
+    #[test]
+    fn test_leader_transaction_share() {
+        let mut subdag = CommittedSubDag {
+            blocks: vec![
+                create_test_verified_block_with_digest(
+                    1,
+                    vec![Transaction::new(vec![1]), Transaction::new(vec![2])],
+                ),
+                create_test_verified_block_with_digest(
+                    2,
+                    vec![Transaction::new(vec![3]), Transaction::new(vec![4])],
+                ),
+            ],
+            leader: create_test_block_ref(1),
+            ..Default::default()
+        };
+        assert_eq!(subdag.leader_transaction_share(), 0.5);
+        subdag.leader = create_test_block_ref(9);
+        assert_eq!(subdag.leader_transaction_share(), 0.0);
+    }
+
+    #[test]
+    fn test_short_id() {
+        let mut commit_ref = create_test_commit_ref(42);
+        commit_ref.digest[..3].copy_from_slice(&[0xab, 0xcd, 0xef]);
+        let subdag = CommittedSubDag {
+            commit_ref,
+            ..Default::default()
+        };
+        assert_eq!(subdag.short_id(), "R42-abcdef");
+    }
+
+    #[test]
+    fn test_blocks_with_signing_status() {
+        let subdag = CommittedSubDag {
+            blocks: vec![
+                create_test_signed_verified_block(vec![Transaction::new(vec![1])]),
+                create_test_verified_block(vec![Transaction::new(vec![2])]),
+            ],
+            ..Default::default()
+        };
+        let statuses: Vec<_> = subdag
+            .blocks_with_signing_status()
+            .map(|(block, signed)| (block.block.transactions()[0].data()[0], signed))
+            .collect();
+        assert_eq!(statuses, vec![(1, true), (2, false)]);
+    }
+
+    #[cfg(feature = "sha2")]
+    #[test]
+    fn test_transaction_bloom() {
+        let transactions: Vec<_> = (0u8..20).map(|i| Transaction::new(vec![i, 1])).collect();
+        let subdag = CommittedSubDag {
+            leader: create_test_block_ref(1),
+            blocks: vec![create_test_verified_block(transactions.clone())],
+            timestamp_ms: 1000,
+            commit_ref: create_test_commit_ref(1),
+            reputation_scores_desc: vec![],
+        };
+        let filter = subdag.transaction_bloom(1024);
+        assert_eq!(filter.len(), 128);
+        assert!(transactions.iter().all(|tx| bloom_contains(&filter, tx)));
+
+        let false_positives = (0u8..100)
+            .map(|i| Transaction::new(vec![i, 2]))
+            .filter(|tx| bloom_contains(&filter, tx))
+            .count();
+        assert!(false_positives < 10, "{false_positives} false positives");
+    }
+
+    #[cfg(feature = "sha2")]
+    #[test]
+    fn test_transaction_bloom_empty() {
+        let subdag = CommittedSubDag {
+            leader: create_test_block_ref(1),
+            blocks: vec![create_test_verified_block(vec![])],
+            timestamp_ms: 1000,
+            commit_ref: create_test_commit_ref(1),
+            reputation_scores_desc: vec![],
+        };
+        let tx = Transaction::new(vec![1]);
+        assert!(!bloom_contains(&subdag.transaction_bloom(64), &tx));
+        assert_eq!(subdag.transaction_bloom(0), Vec::<u8>::new());
+        assert!(bloom_contains(&[], &tx));
+    }
+
+    #[test]
+    fn test_group_by_selector() {
+        let transfer = [0xa9, 0x05, 0x9c, 0xbb];
+        let approve = [0x09, 0x5e, 0xa7, 0xb3];
+        let tx1 = Transaction::new([&transfer[..], &[1]].concat());
+        let tx2 = Transaction::new(approve.to_vec());
+        let tx3 = Transaction::new([&transfer[..], &[2, 3]].concat());
+        let short = Transaction::new(vec![0xa9, 0x05, 0x9c]);
+        let subdag = CommittedSubDag {
+            leader: create_test_block_ref(1),
+            blocks: vec![
+                create_test_verified_block(vec![tx1.clone(), short]),
+                create_test_verified_block(vec![tx2.clone(), tx3.clone()]),
+            ],
+            timestamp_ms: 1000,
+            commit_ref: create_test_commit_ref(1),
+            reputation_scores_desc: vec![],
+        };
+        let groups = subdag.group_by_selector();
+        assert_eq!(groups.len(), 2);
+        assert_eq!(groups[&transfer], vec![&tx1, &tx3]);
+        assert_eq!(groups[&approve], vec![&tx2]);
+    }
+
+    #[test]
+    fn test_commit_index() {
+        let subdag = CommittedSubDag {
+            leader: create_test_block_ref(10),
+            blocks: vec![],
+            timestamp_ms: 1000,
+            commit_ref: create_test_commit_ref(10),
+            reputation_scores_desc: vec![],
+        };
+        assert_eq!(subdag.commit_index(4), Some(6));
+        assert_eq!(subdag.commit_index(10), Some(0));
+        assert_eq!(subdag.commit_index(11), None);
+    }
+
+    #[test]
+    fn test_push_transaction_to_last_block() {
+        let mut subdag = CommittedSubDag {
+            leader: create_test_block_ref(1),
+            blocks: vec![
+                create_test_verified_block(vec![Transaction::new(vec![1])]),
+                create_test_verified_block(vec![Transaction::new(vec![2])]),
+            ],
+            timestamp_ms: 1000,
+            commit_ref: create_test_commit_ref(1),
+            reputation_scores_desc: vec![],
+        };
+        let digest = subdag.blocks[1].digest;
+        subdag
+            .push_transaction_to_last_block(Transaction::new(vec![3]))
+            .unwrap();
+        assert_eq!(subdag.blocks[0].block.transactions().len(), 1);
+        assert_eq!(
+            subdag.blocks[1].block.transactions(),
+            &vec![Transaction::new(vec![2]), Transaction::new(vec![3])]
+        );
+        assert_eq!(subdag.blocks[1].digest, digest);
+    }
+
+    #[test]
+    fn test_push_transaction_to_last_block_no_blocks() {
+        let mut subdag = CommittedSubDag::default();
+        assert_eq!(
+            subdag.push_transaction_to_last_block(Transaction::new(vec![1])),
+            Err(NoBlocksError)
+        );
+        assert!(subdag.is_empty());
+    }
+
+    #[test]
+    fn test_empty_block_ratio() {
+        let all_empty = CommittedSubDag {
+            blocks: vec![
+                create_test_verified_block(vec![]),
+                create_test_verified_block(vec![]),
+            ],
+            ..Default::default()
+        };
+        assert_eq!(all_empty.empty_block_ratio(), 1.0);
+
+        let none_empty = CommittedSubDag {
+            blocks: vec![create_test_verified_block(vec![Transaction::new(vec![1])])],
+            ..Default::default()
+        };
+        assert_eq!(none_empty.empty_block_ratio(), 0.0);
+
+        let mixed = CommittedSubDag {
+            blocks: vec![
+                create_test_verified_block(vec![Transaction::new(vec![1])]),
+                create_test_verified_block(vec![]),
+                create_test_verified_block(vec![Transaction::new(vec![2])]),
+                create_test_verified_block(vec![]),
+            ],
+            ..Default::default()
+        };
+        assert_eq!(mixed.empty_block_ratio(), 0.5);
+        assert_eq!(CommittedSubDag::default().empty_block_ratio(), 0.0);
+    }
+
+    #[test]
+    fn test_group_by_prefix() {
+        let subdag = CommittedSubDag {
+            blocks: vec![
+                create_test_verified_block(vec![
+                    Transaction::new(vec![1, 2, 3, 4]),
+                    Transaction::new(vec![1, 2, 9]),
+                ]),
+                create_test_verified_block(vec![
+                    Transaction::new(vec![1, 3, 3]),
+                    Transaction::new(vec![1]),
+                ]),
+            ],
+            ..Default::default()
+        };
+        let groups = subdag.group_by_prefix(2);
+        assert_eq!(
+            groups,
+            HashMap::from([(vec![1, 2], 2), (vec![1, 3], 1), (vec![1], 1)])
+        );
+        assert_eq!(subdag.group_by_prefix(0), HashMap::from([(vec![], 4)]));
+        assert!(CommittedSubDag::default().group_by_prefix(2).is_empty());
+    }
+
+    #[test]
+    fn test_expect_transaction_count() {
+        let subdag = CommittedSubDag {
+            blocks: vec![
+                create_test_verified_block(vec![Transaction::new(vec![1])]),
+                create_test_verified_block(vec![
+                    Transaction::new(vec![2]),
+                    Transaction::new(vec![3]),
+                ]),
+            ],
+            ..Default::default()
+        };
+        assert_eq!(subdag.expect_transaction_count(3), Ok(()));
+        assert_eq!(
+            subdag.expect_transaction_count(4),
+            Err(CountMismatch {
+                expected: 4,
+                actual: 3
+            })
+        );
+        assert_eq!(
+            CommittedSubDag::default().expect_transaction_count(0),
+            Ok(())
+        );
+    }
+
+    #[test]
+    fn test_to_blob_with_index() {
+        let transactions = vec![
+            Transaction::new(vec![1, 2, 3]),
+            Transaction::new(vec![]),
+            Transaction::new(vec![4]),
+            Transaction::new(vec![5, 6]),
+        ];
+        let subdag = CommittedSubDag {
+            blocks: vec![
+                create_test_verified_block(transactions[..2].to_vec()),
+                create_test_verified_block(transactions[2..].to_vec()),
+            ],
+            ..Default::default()
+        };
+        let (blob, index) = subdag.to_blob_with_index();
+        assert_eq!(blob, vec![1, 2, 3, 4, 5, 6]);
+        assert_eq!(index, vec![(0, 3), (3, 0), (3, 1), (4, 2)]);
+        let rebuilt: Vec<_> = index
+            .iter()
+            .map(|(offset, len)| Transaction::new(blob[*offset..offset + len].to_vec()))
+            .collect();
+        assert_eq!(rebuilt, transactions);
+    }
+
+    #[test]
+    fn test_median_transaction_size() {
+        let odd = CommittedSubDag {
+            blocks: vec![create_test_verified_block(vec![
+                Transaction::new(vec![0; 100]),
+                Transaction::new(vec![0; 1]),
+                Transaction::new(vec![0; 5]),
+            ])],
+            ..Default::default()
+        };
+        assert_eq!(odd.median_transaction_size(), Some(5));
+
+        let even = CommittedSubDag {
+            blocks: vec![
+                create_test_verified_block(vec![
+                    Transaction::new(vec![0; 8]),
+                    Transaction::new(vec![0; 2]),
+                ]),
+                create_test_verified_block(vec![
+                    Transaction::new(vec![0; 3]),
+                    Transaction::new(vec![0; 1000]),
+                ]),
+            ],
+            ..Default::default()
+        };
+        assert_eq!(even.median_transaction_size(), Some(5));
+
+        assert_eq!(CommittedSubDag::default().median_transaction_size(), None);
+    }
+
+    #[test]
+    fn test_leader_in_set() {
+        let mut subdag = CommittedSubDag::default();
+        subdag.leader.leader_address = "0x00000000000000000000000000000000000000aa".to_string();
+        let allowed = HashSet::from([
+            "0x00000000000000000000000000000000000000aa".to_string(),
+            "0x00000000000000000000000000000000000000bb".to_string(),
+        ]);
+        assert!(subdag.leader_in_set(&allowed));
+
+        subdag.leader.leader_address = "0x00000000000000000000000000000000000000cc".to_string();
+        assert!(!subdag.leader_in_set(&allowed));
+        subdag.leader.leader_address = "0x00000000000000000000000000000000000000AA".to_string();
+        assert!(!subdag.leader_in_set(&allowed));
+    }
+
+    #[test]
+    fn test_block_transaction_counts() {
+        let subdag = CommittedSubDag {
+            blocks: vec![
+                create_test_verified_block(vec![
+                    Transaction::new(vec![1]),
+                    Transaction::new(vec![2]),
+                ]),
+                create_test_verified_block(vec![]),
+                create_test_verified_block(vec![Transaction::new(vec![3])]),
+            ],
+            ..Default::default()
+        };
+        assert_eq!(subdag.block_transaction_counts(), vec![2, 0, 1]);
+        assert!(CommittedSubDag::default()
+            .block_transaction_counts()
+            .is_empty());
+    }
+
+    #[test]
+    fn test_all_transactions_within() {
+        let subdag = CommittedSubDag {
+            blocks: vec![
+                create_test_verified_block(vec![
+                    Transaction::new(vec![0; 4]),
+                    Transaction::new(vec![0; 8]),
+                ]),
+                create_test_verified_block(vec![
+                    Transaction::new(vec![0; 2]),
+                    Transaction::new(vec![0; 9]),
+                    Transaction::new(vec![0; 12]),
+                ]),
+            ],
+            ..Default::default()
+        };
+        assert_eq!(subdag.all_transactions_within(12), Ok(()));
+        assert_eq!(
+            subdag.all_transactions_within(8),
+            Err(OversizedTransaction {
+                index: 3,
+                size: 9,
+                max_bytes: 8
+            })
+        );
+        assert_eq!(
+            CommittedSubDag::default().all_transactions_within(0),
+            Ok(())
+        );
+    }
+
+    #[test]
+    fn test_into_tagged_transactions() {
+        let subdag = CommittedSubDag {
+            leader: create_test_block_ref(7),
+            blocks: vec![
+                create_test_verified_block_with_digest(
+                    1,
+                    vec![Transaction::new(vec![1]), Transaction::new(vec![2])],
+                ),
+                create_test_verified_block_with_digest(2, vec![]),
+                create_test_verified_block_with_digest(3, vec![Transaction::new(vec![3])]),
+            ],
+            timestamp_ms: 1000,
+            commit_ref: create_test_commit_ref(7),
+            reputation_scores_desc: vec![],
+        };
+        let digests: Vec<_> = subdag.blocks.iter().map(|block| block.digest).collect();
+        let expected_len = subdag.len();
+        let tagged = subdag.into_tagged_transactions();
+        assert_eq!(tagged.len(), expected_len);
+        assert!(tagged.iter().all(|record| record.commit_round == 7));
+        let rows: Vec<_> = tagged
+            .iter()
+            .map(|record| (record.block_digest, record.data.clone()))
+            .collect();
+        assert_eq!(
+            rows,
+            vec![
+                (digests[0], vec![1]),
+                (digests[0], vec![2]),
+                (digests[2], vec![3])
+            ]
+        );
+    }
+
+    #[test]
+    fn test_transaction_order_keys() {
+        let subdag = CommittedSubDag {
+            blocks: vec![
+                create_test_verified_block(vec![
+                    Transaction::new(vec![1]),
+                    Transaction::new(vec![2]),
+                ]),
+                create_test_verified_block(vec![]),
+                create_test_verified_block(vec![Transaction::new(vec![3])]),
+            ],
+            commit_ref: create_test_commit_ref(5),
+            ..Default::default()
+        };
+        let keys = subdag.transaction_order_keys();
+        assert_eq!(keys, vec![(5, 0, 0), (5, 0, 1), (5, 2, 0)]);
+        assert_eq!(keys.len(), subdag.len());
+        assert!(keys.windows(2).all(|pair| pair[0] < pair[1]));
+    }
+
+    #[test]
+    fn test_into_signed_blocks() {
+        let subdag = CommittedSubDag {
+            blocks: vec![
+                create_test_verified_block(vec![
+                    Transaction::new(vec![1]),
+                    Transaction::new(vec![2]),
+                ]),
+                create_test_signed_verified_block(vec![Transaction::new(vec![3])]),
+            ],
+            ..Default::default()
+        };
+        let expected: Vec<_> = subdag
+            .blocks
+            .iter()
+            .map(|block| block.block.clone())
+            .collect();
+        let blocks = subdag.into_signed_blocks();
+        assert_eq!(blocks, expected);
+        assert_eq!(blocks[1].transactions(), &vec![Transaction::new(vec![3])]);
+        assert!(blocks[1].is_signed());
+    }
+
+    #[test]
+    fn test_verified_block_require_non_empty() {
+        let block = create_test_verified_block(vec![Transaction::new(vec![1])]);
+        assert_eq!(block.require_non_empty(), Ok(()));
+        let empty = create_test_verified_block_with_digest(9, vec![]);
+        assert_eq!(
+            empty.require_non_empty(),
+            Err(EmptyBlockError {
+                digest: empty.digest
+            })
+        );
+    }
+
+    #[test]
+    fn test_storage_footprint() {
+        let empty = CommittedSubDag::default();
+        let metadata_size = crate::COMPACT_METADATA_HEADER_SIZE;
+        assert_eq!(empty.storage_footprint(), metadata_size);
+
+        let small = CommittedSubDag {
+            blocks: vec![create_test_verified_block(vec![Transaction::new(vec![
+                0;
+                10
+            ])])],
+            ..Default::default()
+        };
+        assert_eq!(
+            small.storage_footprint(),
+            metadata_size + 10 + BLOCK_STORAGE_OVERHEAD
+        );
+
+        let large = CommittedSubDag {
+            blocks: vec![
+                create_test_verified_block(vec![Transaction::new(vec![0; 10])]),
+                create_test_signed_verified_block(vec![
+                    Transaction::new(vec![0; 100]),
+                    Transaction::new(vec![0; 50]),
+                ]),
+            ],
+            ..Default::default()
+        };
+        assert_eq!(
+            large.storage_footprint(),
+            metadata_size + 160 + 2 * BLOCK_STORAGE_OVERHEAD + 64
+        );
+        assert!(large.storage_footprint() > small.storage_footprint());
+    }
+
+    #[cfg(feature = "tokio")]
+    #[test]
+    fn test_collect_from_stream() {
+        let blocks = vec![
+            create_test_verified_block_with_digest(1, vec![Transaction::new(vec![1])]),
+            create_test_verified_block_with_digest(2, vec![]),
+            create_test_verified_block_with_digest(
+                3,
+                vec![Transaction::new(vec![2]), Transaction::new(vec![3])],
+            ),
+        ];
+        let subdag = tokio_test::block_on(CommittedSubDag::collect_from_stream(
+            futures::stream::iter(blocks.clone()),
+            create_test_block_ref(4),
+            create_test_commit_ref(4),
+            4000,
+        ));
+        assert_eq!(subdag.blocks, blocks);
+        assert_eq!(subdag.len(), 3);
+        assert_eq!(subdag.commit_ref, create_test_commit_ref(4));
+        assert_eq!(subdag.timestamp_ms, 4000);
+    }
+
+    #[test]
+    fn test_shared_blocks() {
+        let subdag = create_test_subdag_with_digests(&[1, 2, 3]);
+        assert_eq!(
+            subdag.shared_blocks(&create_test_subdag_with_digests(&[3, 1, 2])),
+            subdag
+                .blocks
+                .iter()
+                .map(|block| block.digest)
+                .collect::<Vec<_>>()
+        );
+        assert!(subdag
+            .shared_blocks(&create_test_subdag_with_digests(&[4, 5]))
+            .is_empty());
+        let partial = subdag.shared_blocks(&create_test_subdag_with_digests(&[5, 3, 2]));
+        assert_eq!(
+            partial,
+            vec![subdag.blocks[1].digest, subdag.blocks[2].digest]
+        );
+        assert!(subdag.shared_blocks(&CommittedSubDag::default()).is_empty());
+    }
+
+    #[test]
+    fn test_estimate_unique_senders() {
+        let subdag = CommittedSubDag {
+            blocks: vec![
+                create_test_verified_block(vec![
+                    Transaction::new(vec![0, 0xaa, 0xaa, 1]),
+                    Transaction::new(vec![0, 0xbb, 0xbb, 2]),
+                ]),
+                create_test_verified_block(vec![
+                    Transaction::new(vec![9, 0xaa, 0xaa, 3]),
+                    Transaction::new(vec![0, 0xaa]),
+                ]),
+            ],
+            ..Default::default()
+        };
+        assert_eq!(subdag.estimate_unique_senders(1..3), 2);
+        assert_eq!(subdag.estimate_unique_senders(0..1), 2);
+        assert_eq!(subdag.estimate_unique_senders(3..4), 3);
+        assert_eq!(subdag.estimate_unique_senders(2..8), 0);
+    }
+
+    #[test]
+    fn test_leader_round_matches_commit() {
+        let mut subdag = CommittedSubDag {
+            leader: create_test_block_ref(8),
+            commit_ref: create_test_commit_ref(8),
+            ..Default::default()
+        };
+        assert!(subdag.leader_round_matches_commit());
+        subdag.leader.round = 7;
+        assert!(!subdag.leader_round_matches_commit());
+    }
 }
@@ -1,9 +1,15 @@
 use crate::types::{AuthorityIndex, BlockRef, CommitRef};
+use crate::{BlockDigest, CommittedSubDag, SignedBlock, VerifiedBlock};
 
 /// A helper structure for working with committed subdags containing generic transaction types.
 /// This type is not serializable by design - consumers should convert to their own types
-/// or use `CommittedSubDag` for serialization.
+/// or use `CommittedSubDag` for serialization. With the `serde-generic` feature it implements
+/// `Serialize`/`Deserialize` whenever the transaction type does.
 #[derive(Debug, Clone)]
+#[cfg_attr(
+    feature = "serde-generic",
+    derive(serde::Serialize, serde::Deserialize)
+)]
 pub struct MysticetiCommittedSubdag<Transaction> {
     pub leader: BlockRef,
     pub transactions: Vec<Transaction>,
@@ -12,6 +18,38 @@ pub struct MysticetiCommittedSubdag<Transaction> {
     pub reputation_scores_desc: Vec<(AuthorityIndex, u64)>,
 }
 
+impl MysticetiCommittedSubdag<crate::types::Transaction> {
+    /// Convert into a `CommittedSubDag`, grouping the transactions into unsigned blocks of
+    /// `txs_per_block` transactions each.
+    ///
+    /// The last block holds the remaining transactions and may be smaller. A `txs_per_block` of
+    /// 0 puts all transactions into a single block. Block digests are left at their default value
+    /// since the original blocks are unknown.
+    pub fn into_subdag_with_block_size(self, txs_per_block: usize) -> CommittedSubDag {
+        let chunk_size = if txs_per_block == 0 {
+            self.transactions.len().max(1)
+        } else {
+            txs_per_block
+        };
+        let mut blocks = Vec::with_capacity(self.transactions.len().div_ceil(chunk_size));
+        let mut transactions = self.transactions.into_iter().peekable();
+        while transactions.peek().is_some() {
+            let chunk = transactions.by_ref().take(chunk_size).collect();
+            blocks.push(VerifiedBlock {
+                block: SignedBlock::new(chunk),
+                digest: BlockDigest::default(),
+            });
+        }
+        CommittedSubDag {
+            leader: self.leader,
+            blocks,
+            timestamp_ms: self.timestamp_ms,
+            commit_ref: self.commit_ref,
+            reputation_scores_desc: self.reputation_scores_desc,
+        }
+    }
+}
+
 /// Serialize a batch of raw transaction bytes to JSON.
 /// Consumers can use this to create SubscriptionMessage in their own code.
 pub fn serialize_transactions(batch: Vec<Vec<u8>>) -> Result<String, serde_json::Error> {
@@ -30,7 +68,6 @@ mod tests {
             digest,
             round,
             leader_address: String::new(),
-            ..Default::default()
         }
     }
 
@@ -48,7 +85,7 @@ mod tests {
         let reputation_scores = vec![(0, 100), (1, 90)];
 
         let subdag = MysticetiCommittedSubdag {
-            leader,
+            leader: leader.clone(),
             transactions: transactions.clone(),
             timestamp_ms: 1234567890,
             commit_ref,
@@ -82,6 +119,72 @@ mod tests {
         assert_eq!(subdag1.timestamp_ms, subdag2.timestamp_ms);
     }
 
+    fn create_test_subdag(count: u8) -> MysticetiCommittedSubdag<crate::types::Transaction> {
+        MysticetiCommittedSubdag {
+            leader: create_test_block_ref(1),
+            transactions: (0..count)
+                .map(|i| crate::types::Transaction::new(vec![i]))
+                .collect(),
+            timestamp_ms: 1000,
+            commit_ref: create_test_commit_ref(1),
+            reputation_scores_desc: vec![(0, 100)],
+        }
+    }
+
+    #[test]
+    fn test_into_subdag_with_block_size_exact() {
+        let subdag = create_test_subdag(6).into_subdag_with_block_size(3);
+        assert_eq!(subdag.blocks.len(), 2);
+        assert_eq!(subdag.blocks[0].block.transactions().len(), 3);
+        assert_eq!(subdag.blocks[1].block.transactions().len(), 3);
+        assert_eq!(
+            subdag.flatten_transactions(),
+            (0..6).map(|i| vec![i]).collect::<Vec<_>>()
+        );
+        assert_eq!(subdag.timestamp_ms, 1000);
+        assert_eq!(subdag.reputation_scores_desc, vec![(0, 100)]);
+    }
+
+    #[test]
+    fn test_into_subdag_with_block_size_partial() {
+        let subdag = create_test_subdag(7).into_subdag_with_block_size(3);
+        let sizes: Vec<_> = subdag
+            .blocks
+            .iter()
+            .map(|block| block.block.transactions().len())
+            .collect();
+        assert_eq!(sizes, vec![3, 3, 1]);
+        assert_eq!(
+            create_test_subdag(7)
+                .into_subdag_with_block_size(0)
+                .blocks
+                .len(),
+            1
+        );
+        assert!(create_test_subdag(0)
+            .into_subdag_with_block_size(3)
+            .blocks
+            .is_empty());
+    }
+
+    #[cfg(feature = "serde-generic")]
+    #[test]
+    fn test_mysticeti_committed_subdag_serde_generic() {
+        let subdag = MysticetiCommittedSubdag::<u32> {
+            leader: create_test_block_ref(1),
+            transactions: vec![7, 8, 9],
+            timestamp_ms: 1000,
+            commit_ref: create_test_commit_ref(1),
+            reputation_scores_desc: vec![(0, 100)],
+        };
+        let json = serde_json::to_string(&subdag).unwrap();
+        assert!(json.contains(r#""transactions":[7,8,9]"#));
+        let decoded: MysticetiCommittedSubdag<u32> = serde_json::from_str(&json).unwrap();
+        assert_eq!(decoded.transactions, subdag.transactions);
+        assert_eq!(decoded.leader, subdag.leader);
+        assert_eq!(decoded.commit_ref, subdag.commit_ref);
+    }
+
     #[test]
     fn test_serialize_transactions_empty() {
         let batch: Vec<Vec<u8>> = vec![];